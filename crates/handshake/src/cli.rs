@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+use clap::{arg, ArgGroup, Command};
+use protocol::profiles::Profile;
+
+pub fn command() -> Command {
+    let command = Command::new("handshake")
+        .about("Stellar overlay handshake and peer tooling")
+        .subcommand_required(true)
+        .args(&[
+            arg!(-n --network <NETWORK> "Built-in profile the configuration starts from, pubnet by default")
+                .value_parser(|name: &str| name.parse::<Profile>())
+                .global(true),
+            arg!(-m --mainnet "Same as --network pubnet").global(true),
+            arg!(-l --localnet "Same as --network standalone").global(true),
+            arg!(-p --path <FILE> "Configuration file overriding the profile, configuration/handshake.yaml if present by default")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true),
+            arg!(-k --"seed-file" <FILE> "File holding the node seed, overrides seed and seed_file of the configuration")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true),
+        ])
+        .group(ArgGroup::new("profile")
+            .args(["network", "mainnet", "localnet"])
+            .multiple(false))
+        .subcommands([
+            Command::new("handshake")
+                .about("Connects to a node, runs the handshake and reports the result")
+                .arg(arg!([ADDR] "host:port of the node, the configured node by default")),
+            Command::new("listen")
+                .about("Accepts inbound connections and logs what the peers send")
+                .arg(arg!([ADDR] "Address to listen on, 0.0.0.0 and the configured listening port by default")),
+            Command::new("probe")
+                .about("Runs the handshake with many nodes at once and reports the result of each")
+                .args(&[
+                    arg!([ADDR] ... "host:port of the nodes, the configured known_peers if neither these nor --file are given"),
+                    arg!(-f --file <FILE> "File with a host:port per line, - for stdin; empty lines and lines starting with # are skipped"),
+                    arg!(-j --jobs <N> "How many handshakes run at once")
+                        .value_parser(clap::value_parser!(u16).range(1..))
+                        .default_value("16"),
+                    arg!(--format <FORMAT> "How the results are printed")
+                        .value_parser(["table", "json"])
+                        .default_value("table"),
+                ]),
+            Command::new("crawl")
+                .about("Discovers the overlay by asking peers for their peers and exports the graph")
+                .args(&[
+                    arg!([ADDR] ... "host:port of the nodes to start from, the configured node by default"),
+                    arg!(-d --depth <N> "How many hops from the start nodes peers are still visited")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("2"),
+                    arg!(--"max-peers" <N> "How many peers are visited at most")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("100"),
+                    arg!(-j --jobs <N> "How many peers are visited at once")
+                        .value_parser(clap::value_parser!(u16).range(1..))
+                        .default_value("16"),
+                    arg!(--format <FORMAT> "How the graph is printed")
+                        .value_parser(["json", "dot"])
+                        .default_value("json"),
+                ]),
+            Command::new("keygen")
+                .about("Generates a node seed and prints it with its node id")
+                .args(&[
+                    arg!(-o --output <FILE> "Writes the seed to a new file readable only by the owner instead of printing it")
+                        .value_parser(clap::value_parser!(PathBuf)),
+                    arg!(--force "Overwrites the output file if it exists"),
+                ]),
+            Command::new("decode")
+                .about("Decodes XDR from a file or stdin")
+                .args(&[
+                    arg!([FILE] "Input file, stdin if absent or -"),
+                    arg!(-t --type <TYPE> "What the input holds")
+                        .value_parser(["frames", "authenticated-message", "stellar-message"])
+                        .default_value("frames"),
+                    arg!(--base64 "The input is base64 encoded"),
+                ]),
+            Command::new("watch")
+                .about("Connects to a node and prints every message it sends")
+                .arg(arg!([ADDR] "host:port of the node, the configured node by default")),
+        ]);
+    #[cfg(unix)]
+    let command = command.subcommand(
+        Command::new("signer")
+            .about("Signs for other runs of this app over a Unix socket, so that they never hold the seed")
+            .arg(arg!(<SOCKET> "Path of the Unix socket to create").value_parser(clap::value_parser!(PathBuf))),
+    );
+    command
+}
@@ -0,0 +1,70 @@
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+use protocol::errors::StellarError;
+use protocol::protocol::ProtocolMessage;
+use xdr::compound_types::{record_length, XdrArchive};
+use xdr::types::AuthenticatedMessage;
+use xdr::{DecodeError, XdrCodec};
+
+pub use protocol::protocol::DEFAULT_MAX_FRAME_SIZE;
+
+/// Record marked `AuthenticatedMessage` frames. Decoded frames come with their payload,
+/// the bytes the MAC is computed over.
+pub struct AuthenticatedMessageCodec {
+    max_frame_size: usize,
+}
+
+impl AuthenticatedMessageCodec {
+    pub fn new(max_frame_size: usize) -> Self {
+        Self { max_frame_size }
+    }
+
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+}
+
+impl Default for AuthenticatedMessageCodec {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_FRAME_SIZE)
+    }
+}
+
+impl Decoder for AuthenticatedMessageCodec {
+    type Item = (XdrArchive<AuthenticatedMessage>, Vec<u8>);
+    type Error = StellarError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, StellarError> {
+        let Some(size) = record_length(src, self.max_frame_size)? else {
+            return Ok(None);
+        };
+        let frame = &src[..size];
+        let (message, _) = <XdrArchive<AuthenticatedMessage> as ProtocolMessage>::decoded(frame)?;
+        let payload = XdrArchive::<AuthenticatedMessage>::payload(frame, self.max_frame_size)?;
+        src.advance(size);
+        Ok(Some((message, payload)))
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, StellarError> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if src.is_empty() => Ok(None),
+            None => Err(StellarError::ConnectionResetByPeer),
+        }
+    }
+}
+
+impl Encoder<XdrArchive<AuthenticatedMessage>> for AuthenticatedMessageCodec {
+    type Error = StellarError;
+
+    fn encode(&mut self, message: XdrArchive<AuthenticatedMessage>, dst: &mut BytesMut) -> Result<(), StellarError> {
+        for item in message.get_vec() {
+            let length = XdrCodec::to_xdr(item).len();
+            if length > self.max_frame_size {
+                return Err(DecodeError::RecordTooLarge { requested_length: length, allowed_length: self.max_frame_size }.into());
+            }
+        }
+        dst.extend_from_slice(&ProtocolMessage::to_xdr(&message));
+        Ok(())
+    }
+}
@@ -0,0 +1,370 @@
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use data_encoding::BASE64;
+use futures::{stream, StreamExt};
+use log::{info, warn};
+use tokio::net::{lookup_host, TcpListener};
+use protocol::connection_authentication::PeerRole;
+use protocol::errors::StellarError;
+use protocol::keychain::{Keychain, KeychainError};
+use protocol::node_config::NodeConfig;
+use protocol::profiles::Profile;
+use protocol::secret::Secret;
+use protocol::signer::{AsyncSigner, SignerError};
+use protocol::protocol::ProtocolMessage;
+use protocol::stellar_protocol::StellarProtocol;
+use utils::strkey::encode_public_key;
+use utils::misc::generate_encoded_seed;
+use xdr::compound_types::{VarArray, XdrArchive};
+use xdr::types::{AuthenticatedMessage, StellarMessage};
+use xdr::XdrCodec;
+use handshake::connection::Connection;
+use handshake::crawler::{CrawlLimits, Crawler};
+use handshake::handshake::{connect, create_protocol, execute_handshake};
+use handshake::peer_handle::{Inbound, PeerHandle};
+#[cfg(unix)]
+use handshake::unix_signer::{self, UnixSocketSigner};
+use crate::report::{self, ProbeResult};
+
+/// The handshake or the data was rejected.
+pub const EXIT_FAILURE: u8 = 1;
+/// Bad arguments, configuration or input, the code clap uses for usage errors too.
+pub const EXIT_USAGE: u8 = 2;
+/// The node could not be reached or the connection was lost.
+pub const EXIT_UNREACHABLE: u8 = 3;
+pub const EXIT_TIMEOUT: u8 = 4;
+
+/// Some of the peers `probe` ran the handshake with failed it.
+#[derive(Debug, thiserror::Error)]
+#[error("{failed} of {total} peers failed")]
+pub struct ProbeFailed {
+    pub failed: usize,
+    pub total: usize,
+}
+
+pub fn exit_code(error: &anyhow::Error) -> u8 {
+    if let Some(error) = error.downcast_ref::<StellarError>() {
+        match error {
+            StellarError::Timeout { .. } => EXIT_TIMEOUT,
+            StellarError::IOError(_) | StellarError::ConnectionResetByPeer | StellarError::ExpectedMoreMessages => EXIT_UNREACHABLE,
+            StellarError::InvalidConfig { .. } => EXIT_USAGE,
+            _ => EXIT_FAILURE,
+        }
+    } else if error.is::<SignerError>() {
+        EXIT_UNREACHABLE
+    } else if error.is::<config::ConfigError>() || error.is::<KeychainError>() || error.is::<std::io::Error>() || error.is::<data_encoding::DecodeError>() {
+        EXIT_USAGE
+    } else {
+        EXIT_FAILURE
+    }
+}
+
+/// Prefix of the environment variables overriding the configuration, e.g. `STELLAR_HANDSHAKE_IP`;
+/// nested keys are separated by `__` as in `STELLAR_HANDSHAKE_TIMEOUTS__IDLE_MS`.
+const ENV_PREFIX: &str = "STELLAR_HANDSHAKE";
+/// Keys whose environment variables hold comma separated lists.
+const ENV_LIST_KEYS: [&str; 3] = ["known_peers", "allowed_peers", "denied_peers"];
+
+/// Loads the configuration in layers: the profile selected by `--network` (pubnet by default),
+/// the file given by `--path` or else `configuration/handshake.yaml` if it exists, and the environment.
+pub fn load_config(matches: &ArgMatches) -> Result<NodeConfig> {
+    let profile = if matches.get_flag("mainnet") {
+        Profile::Pubnet
+    } else if matches.get_flag("localnet") {
+        Profile::Standalone
+    } else {
+        matches.get_one::<Profile>("network").copied().unwrap_or_default()
+    };
+    let file = match matches.get_one::<PathBuf>("path") {
+        Some(path) => config::File::from(path.as_path()),
+        None => config::File::from(std::env::current_dir()?.join("configuration").join("handshake.yaml")).required(false),
+    };
+    let environment = ENV_LIST_KEYS.into_iter().fold(
+        config::Environment::with_prefix(ENV_PREFIX)
+            .prefix_separator("_")
+            .separator("__")
+            .try_parsing(true)
+            .list_separator(","),
+        |environment, key| environment.with_list_parse_key(key),
+    );
+    let mut builder = config::Config::builder()
+        .add_source(config::File::from_str(profile.yaml(), config::FileFormat::Yaml))
+        .add_source(file)
+        .add_source(environment);
+    if let Some(seed_file) = matches.get_one::<PathBuf>("seed-file") {
+        builder = builder
+            .set_override("seed", None::<String>)?
+            .set_override("seed_file", seed_file.to_string_lossy().as_ref())?;
+    }
+    let settings = builder.build()?;
+    let node_config = settings.try_deserialize::<NodeConfig>()?;
+    node_config.validate()?;
+    Ok(node_config)
+}
+
+/// The signer of the configured socket, else our keychain from the configured seed or a new one for this run.
+async fn load_signer(node_config: &NodeConfig) -> Result<Arc<dyn AsyncSigner>> {
+    let signer: Arc<dyn AsyncSigner> = match &node_config.signer_socket {
+        #[cfg(unix)]
+        Some(path) => Arc::new(UnixSocketSigner::connect(path).await?),
+        #[cfg(not(unix))]
+        Some(_) => {
+            let message = "the signer is reached over a Unix socket, which this platform lacks".into();
+            return Err(StellarError::InvalidConfig { field: "signer_socket".into(), message }.into());
+        }
+        None => Arc::new(load_keychain(node_config)?),
+    };
+    info!("node id {}", encode_public_key(&signer.public_key()));
+    Ok(signer)
+}
+
+/// Our keychain from the configured seed, or a new one for this run.
+fn load_keychain(node_config: &NodeConfig) -> Result<Keychain> {
+    match (&node_config.seed, &node_config.seed_file) {
+        (Some(seed), _) => Ok(Keychain::from_encoded_seed(seed.expose())?),
+        (None, Some(path)) => Ok(Keychain::from_seed_file(path)?),
+        (None, None) => {
+            info!("no seed configured, generating a node id for this run");
+            Ok(Keychain::from_encoded_seed(Secret::new(generate_encoded_seed()).expose())?)
+        }
+    }
+}
+
+async fn resolve(address: Option<&String>, node_config: &NodeConfig) -> Result<SocketAddr, StellarError> {
+    let address = address.cloned().unwrap_or_else(|| node_config.address());
+    let resolved = lookup_host(address.as_str()).await?.next();
+    resolved.ok_or_else(|| {
+        StellarError::IOError(std::io::Error::new(std::io::ErrorKind::NotFound, format!("{} does not resolve", address)))
+    })
+}
+
+pub async fn handshake(matches: &ArgMatches, node_config: &NodeConfig) -> Result<()> {
+    let address = resolve(matches.get_one::<String>("ADDR"), node_config).await?;
+    let (_, peer) = connect(address, node_config, load_signer(node_config).await?.as_ref()).await?;
+    println!("{}: handshake completed", address);
+    println!("node id:          {}", peer.node_id);
+    println!("version:          {}", peer.version_str);
+    println!("ledger version:   {}", peer.ledger_version);
+    println!("overlay versions: {}..={}, negotiated {}", peer.overlay_min_version, peer.overlay_version, peer.negotiated_overlay_version);
+    println!("listening port:   {}", peer.listening_port);
+    println!("cert expiration:  {}", peer.cert_expiration);
+    Ok(())
+}
+
+/// Runs the handshake with the given nodes, those of `--file` and else the configured `known_peers`,
+/// `--jobs` at a time, and prints a result for each in the order of the input.
+pub async fn probe(matches: &ArgMatches, node_config: &NodeConfig) -> Result<()> {
+    let mut addresses: Vec<String> = matches.get_many::<String>("ADDR").map(|addresses| addresses.cloned().collect()).unwrap_or_default();
+    if let Some(file) = matches.get_one::<String>("file") {
+        addresses.extend(read_addresses(file).with_context(|| format!("cannot read the addresses from {}", file))?);
+    }
+    if addresses.is_empty() {
+        addresses = node_config.known_peers.clone();
+    }
+    let jobs = *matches.get_one::<u16>("jobs").expect("jobs has a default") as usize;
+    let json = matches.get_one::<String>("format").is_some_and(|format| format == "json");
+    let signer = load_signer(node_config).await?;
+    let mut results = stream::iter(addresses)
+        .map(|address| probe_peer(address, node_config, signer.as_ref()))
+        .buffered(jobs);
+    if !json {
+        report::print_table_header();
+    }
+    let (mut total, mut failed) = (0, 0);
+    while let Some(result) = results.next().await {
+        total += 1;
+        if !result.ok {
+            failed += 1;
+        }
+        if json {
+            println!("{}", result.to_json()?);
+        } else {
+            report::print_table_row(&result);
+        }
+    }
+    if failed > 0 {
+        return Err(ProbeFailed { failed, total }.into());
+    }
+    Ok(())
+}
+
+/// `host:port` per line of a file or of stdin for `-`, without empty lines and `#` comments.
+fn read_addresses(file: &str) -> std::io::Result<Vec<String>> {
+    let mut input = String::new();
+    match file {
+        "-" => std::io::stdin().read_to_string(&mut input)?,
+        path => std::fs::File::open(path)?.read_to_string(&mut input)?,
+    };
+    Ok(input.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+async fn probe_peer(address: String, node_config: &NodeConfig, signer: &dyn AsyncSigner) -> ProbeResult {
+    let socket_address = match resolve(Some(&address), node_config).await {
+        Ok(socket_address) => socket_address,
+        Err(e) => return ProbeResult::new(address, Err(e.into()), None),
+    };
+    let start = Instant::now();
+    let result = connect(socket_address, node_config, signer).await.map(|(_, peer)| peer);
+    ProbeResult::new(address, result, Some(start.elapsed()))
+}
+
+/// Crawls the overlay from the given nodes, the configured one by default, and prints the graph as JSON or DOT.
+pub async fn crawl(matches: &ArgMatches, node_config: &NodeConfig) -> Result<()> {
+    let mut seeds = Vec::new();
+    match matches.get_many::<String>("ADDR") {
+        Some(addresses) => {
+            for address in addresses {
+                seeds.push(resolve(Some(address), node_config).await?);
+            }
+        }
+        None => seeds.push(resolve(None, node_config).await?),
+    }
+    let limits = CrawlLimits {
+        max_depth: *matches.get_one::<usize>("depth").expect("depth has a default"),
+        max_peers: *matches.get_one::<usize>("max-peers").expect("max-peers has a default"),
+        jobs: *matches.get_one::<u16>("jobs").expect("jobs has a default") as usize,
+    };
+    let signer = load_signer(node_config).await?;
+    let graph = Crawler::new(node_config, signer.as_ref(), limits).crawl(seeds).await;
+    match matches.get_one::<String>("format").map(String::as_str) {
+        Some("dot") => print!("{}", graph.to_dot()),
+        _ => println!("{}", graph.to_json()?),
+    }
+    Ok(())
+}
+
+pub async fn watch(matches: &ArgMatches, node_config: &NodeConfig) -> Result<()> {
+    let address = resolve(matches.get_one::<String>("ADDR"), node_config).await?;
+    let (connection, peer) = connect(address, node_config, load_signer(node_config).await?.as_ref()).await?;
+    info!("handshake with {} ({}, {}) completed", address, peer.node_id, peer.version_str);
+    serve(connection).await
+}
+
+pub async fn listen(matches: &ArgMatches, node_config: &NodeConfig) -> Result<()> {
+    let address = match matches.get_one::<String>("ADDR") {
+        Some(address) => address.clone(),
+        None => format!("0.0.0.0:{}", node_config.listening_port),
+    };
+    let listener = TcpListener::bind(address).await.map_err(StellarError::from)?;
+    // advertise the port we listen on instead of the remote node's one
+    let node_config = NodeConfig { listening_port: listener.local_addr()?.port() as i32, ..node_config.clone() };
+    let signer = load_signer(&node_config).await?;
+    info!("listening on {}", listener.local_addr()?);
+    loop {
+        let (socket, remote_address) = listener.accept().await.map_err(StellarError::from)?;
+        info!("accepted connection from {}", remote_address);
+        let node_config = node_config.clone();
+        let signer = signer.clone();
+        tokio::spawn(async move {
+            let result = async {
+                let protocol = create_protocol(&node_config, signer.as_ref(), PeerRole::RemoteCalledUs).await?;
+                let mut connection = Connection::new(protocol, socket, node_config.timeouts.clone());
+                let peer = execute_handshake(&mut connection).await?;
+                info!("handshake with {} ({}, {}) completed", remote_address, peer.node_id, peer.version_str);
+                serve(connection).await
+            }.await;
+            info!("connection from {} closed: {:?}", remote_address, result);
+        });
+    }
+}
+
+/// Logs every message of an authenticated peer until it closes the connection, answering GET_PEERS.
+/// Messages that failed verification are logged as warnings.
+async fn serve(connection: Connection<StellarProtocol>) -> Result<()> {
+    let mut peer = PeerHandle::spawn(connection);
+    let sender = peer.sender();
+    while let Some(inbound) = peer.next().await {
+        let message = match inbound? {
+            Inbound::Message(message) => message,
+            Inbound::Invalid(_, error) => {
+                warn!("dropped an invalid message: {}", error);
+                continue;
+            }
+        };
+        println!("{:#?}", message);
+        if let StellarMessage::GetPeers = message {
+            // we don't track other peers
+            let _ = sender.send(StellarMessage::Peers(VarArray::default())).await;
+        }
+    }
+    Ok(())
+}
+
+pub fn keygen(matches: &ArgMatches) -> Result<()> {
+    let seed = Secret::new(generate_encoded_seed());
+    let keychain = Keychain::from_encoded_seed(seed.expose())?;
+    match matches.get_one::<PathBuf>("output") {
+        Some(path) => {
+            write_seed_file(path, seed.expose(), matches.get_flag("force"))
+                .with_context(|| format!("cannot write the seed to {}", path.display()))?;
+            println!("seed:    written to {}", path.display());
+        }
+        None => println!("seed:    {}", seed.expose()),
+    }
+    println!("node id: {}", keychain.node_id());
+    Ok(())
+}
+
+/// Writes the seed to a file only the owner may read and write, refusing to replace an existing one unless forced.
+fn write_seed_file(path: &Path, seed: &str, force: bool) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true);
+    if force {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let mut file = options.open(path)?;
+        // the mode only applies to new files
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        writeln!(file, "{}", seed)
+    }
+    #[cfg(not(unix))]
+    {
+        writeln!(options.open(path)?, "{}", seed)
+    }
+}
+
+/// Signs the auth certs of other processes of this app over a Unix socket, with the configured seed and network.
+#[cfg(unix)]
+pub async fn signer(matches: &ArgMatches, node_config: &NodeConfig) -> Result<()> {
+    let path = matches.get_one::<PathBuf>("SOCKET").expect("SOCKET is required");
+    let network_id = utils::sha2::create_sha256(node_config.node_info.network_id.as_bytes());
+    unix_signer::serve(path, load_keychain(node_config)?, network_id).await
+}
+
+pub fn decode(matches: &ArgMatches) -> Result<()> {
+    let mut input = Vec::new();
+    match matches.get_one::<String>("FILE").map(String::as_str) {
+        None | Some("-") => std::io::stdin().read_to_end(&mut input)?,
+        Some(path) => std::fs::File::open(Path::new(path))?.read_to_end(&mut input)?,
+    };
+    if matches.get_flag("base64") {
+        let text: Vec<u8> = input.into_iter().filter(|byte| !byte.is_ascii_whitespace()).collect();
+        input = BASE64.decode(&text)?;
+    }
+    match matches.get_one::<String>("type").map(String::as_str) {
+        Some("authenticated-message") => println!("{:#?}", <AuthenticatedMessage as XdrCodec>::decoded(&input).map_err(StellarError::from)?.0),
+        Some("stellar-message") => println!("{:#?}", <StellarMessage as XdrCodec>::decoded(&input).map_err(StellarError::from)?.0),
+        _ => {
+            let (frames, _) = <XdrArchive<AuthenticatedMessage> as ProtocolMessage>::decoded(&input)?;
+            for frame in frames.get_vec() {
+                println!("{:#?}", frame);
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,74 +1,165 @@
-
+use std::collections::VecDeque;
 use std::net::SocketAddr;
-use bytes::BytesMut;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::time::Duration;
+use tokio::time::timeout;
+use futures::{SinkExt, StreamExt};
 use tokio::net::TcpStream;
-use protocol::errors::StellarError;
-use protocol::protocol::{Protocol, ProtocolMessage};
+use tokio_util::codec::Framed;
+use protocol::errors::{StellarError, TimeoutPhase};
+use protocol::node_config::Timeouts;
+use protocol::protocol::Protocol;
+use protocol::session::{Session, SessionEvent};
 use anyhow::Result;
+use xdr::compound_types::XdrArchive;
+use xdr::types::{AuthenticatedMessage, StellarMessage};
+use crate::codec::AuthenticatedMessageCodec;
 pub struct Connection<P: Protocol> {
-    protocol: P,
-    socket: TcpStream,
-    read_buffer: BytesMut,
+    session: Session<P>,
+    framed: Framed<TcpStream, AuthenticatedMessageCodec>,
+    timeouts: Timeouts,
+    /// Messages received while waiting for send capacity.
+    received_messages: VecDeque<StellarMessage>,
 }
-impl<P: Protocol> Connection<P> {
+impl<P: Protocol<Message = XdrArchive<AuthenticatedMessage>>> Connection<P> {
     pub fn new(
         protocol: P,
         socket: TcpStream,
+        timeouts: Timeouts,
+    ) -> Connection<P> {
+        Connection::with_codec(protocol, socket, timeouts, AuthenticatedMessageCodec::default())
+    }
+
+    pub fn with_codec(
+        protocol: P,
+        socket: TcpStream,
+        timeouts: Timeouts,
+        codec: AuthenticatedMessageCodec,
     ) -> Connection<P> {
         Connection {
-            protocol,
-            socket,
-            read_buffer: BytesMut::with_capacity(0x4000),
+            session: Session::new(protocol).with_max_frame_size(codec.max_frame_size()),
+            framed: Framed::new(socket, codec),
+            timeouts,
+            received_messages: VecDeque::new(),
         }
     }
-    pub fn protocol(&mut self) -> &mut P {
-        &mut self.protocol
-    }
 
     pub async fn connect(
         protocol: P,
         addr: SocketAddr,
+        timeouts: Timeouts,
     ) -> Result<Connection<P>, StellarError> {
-        let socket = TcpStream::connect(addr).await?;
-        Ok(Connection::new(protocol, socket))
+        let socket = timeout(Duration::from_millis(timeouts.connect_ms), TcpStream::connect(addr))
+            .await
+            .map_err(|_| StellarError::Timeout { phase: TimeoutPhase::Connect })??;
+        Ok(Connection::new(protocol, socket, timeouts))
+    }
+
+    pub fn timeouts(&self) -> &Timeouts {
+        &self.timeouts
+    }
+
+    /// What the peer told about itself, known once its Hello is verified.
+    pub fn peer_info(&self) -> Option<&P::NodeInfo> {
+        self.session.peer_info()
+    }
+
+    pub fn into_parts(self) -> (Session<P>, Framed<TcpStream, AuthenticatedMessageCodec>, Timeouts, VecDeque<StellarMessage>) {
+        (self.session, self.framed, self.timeouts, self.received_messages)
     }
 
-    pub async fn receive(&mut self) -> Result<Option<(P::Message, Vec<u8>)>> {
+    /// Writes the frames the session wants to send, then reads until it has an event;
+    /// `None` if the peer closed the connection.
+    pub async fn next_event(&mut self) -> Result<Option<SessionEvent>> {
         loop {
-            match self.parse_message() {
-                Ok(Some(result)) => return Ok(Some((result.0, result.1))),
-                Ok(None) => {
-                    if 0 == self.socket.read_buf(&mut self.read_buffer).await? {
-                        return if self.read_buffer.is_empty() {
-                            Ok(None)
-                        } else {
-                            Err(StellarError::ConnectionResetByPeer.into())
-                        };
-                    }
-                },
-                Err(e) => {
-                    return Err(e);
+            self.flush().await?;
+            if let Some(event) = self.session.poll_event() {
+                return Ok(Some(event));
+            }
+            if !self.receive_frame().await? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Writes the frames the session wants to send, then reads and handles one frame;
+    /// `false` if the peer closed the connection.
+    async fn receive_frame(&mut self) -> Result<bool> {
+        self.flush().await?;
+        let Ok(frame) = timeout(idle_timeout(&self.timeouts), self.framed.next()).await else {
+            return Err(idle(&mut self.session).into());
+        };
+        match frame {
+            Some(frame) => {
+                let (message, payload) = frame?;
+                self.session.receive_frame(message, payload)?;
+                Ok(true)
+            }
+            None => {
+                self.session.receive_end()?;
+                Ok(false)
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> Result<(), StellarError> {
+        while let Some(frame) = self.session.poll_transmit() {
+            self.framed.feed(frame).await?;
+        }
+        self.framed.flush().await
+    }
+
+    /// Sends a message to the peer once the handshake is completed,
+    /// waiting until the peer granted the capacity for it.
+    pub async fn send_message(&mut self, message: StellarMessage) -> Result<()> {
+        // SEND_MORE_EXTENDED raises no event, so the capacity is checked after every frame
+        while !self.session.can_send(&message) {
+            if !self.receive_frame().await? {
+                return Err(StellarError::ExpectedMoreMessages.into());
+            }
+            while let Some(event) = self.session.poll_event() {
+                match event {
+                    SessionEvent::Message(received) => self.received_messages.push_back(*received),
+                    SessionEvent::InvalidMessage(..) => {}
+                    SessionEvent::Authenticated => return Err(StellarError::UnexpectedMessage.into()),
                 }
             }
         }
+        self.session.send(message)?;
+        Ok(self.flush().await?)
     }
 
-    fn parse_message(&mut self) -> Result<Option<(P::Message, Vec<u8>)>> {
-        if let Some(size) = P::Message::complete_message_size(self.read_buffer.as_ref()) {
-            let (message, size) = P::Message::decoded(self.read_buffer[..size].as_ref())?;
-            let raw_message = self.read_buffer.split_to(size);
-            Ok(Some((message, raw_message[4..].to_vec())))
-        } else {
-            Ok(None)
+    /// Receives the next verified message from the peer, `None` if the peer closed the connection.
+    /// Messages that failed verification are skipped, [`Connection::next_event`] reports them.
+    pub async fn receive_message(&mut self) -> Result<Option<StellarMessage>> {
+        match self.received_messages.pop_front() {
+            Some(message) => Ok(Some(message)),
+            None => self.read_message().await,
         }
     }
 
-    pub async fn send(&mut self, message: P::Message) -> Result<(), StellarError> {
-        let encoded = message.to_xdr();
-        if let Err(e) = self.socket.write(&encoded).await {
-            return Err(e.into());
+    async fn read_message(&mut self) -> Result<Option<StellarMessage>> {
+        loop {
+            return match self.next_event().await? {
+                Some(SessionEvent::Message(message)) => Ok(Some(*message)),
+                Some(SessionEvent::InvalidMessage(..)) => continue,
+                Some(SessionEvent::Authenticated) => Err(StellarError::UnexpectedMessage.into()),
+                None => Ok(None),
+            };
         }
-        Ok(())
     }
 }
+
+/// How long the peer may stay silent.
+///
+/// We send nothing to keep the connection up: stellar-core floods SCP messages all the time, and we
+/// answer its flooded messages with SEND_MORE_EXTENDED. GET_PEERS is no keepalive, stellar-core
+/// answers it once per connection at most.
+pub fn idle_timeout(timeouts: &Timeouts) -> Duration {
+    Duration::from_millis(timeouts.idle_ms)
+}
+
+/// The peer was silent for too long: closes the session.
+pub fn idle<P: Protocol<Message = XdrArchive<AuthenticatedMessage>>>(session: &mut Session<P>) -> StellarError {
+    session.close();
+    StellarError::Timeout { phase: TimeoutPhase::Idle }
+}
@@ -0,0 +1,210 @@
+//! Discovers the overlay: runs the handshake with the seeds, asks each peer for the peers it knows
+//! with GET_PEERS and goes on with the peers learned, breadth first, up to a depth and a count limit.
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use anyhow::Result;
+use futures::{stream, StreamExt};
+use log::info;
+use serde::Serialize;
+use tokio::time::timeout;
+use protocol::errors::{StellarError, TimeoutPhase};
+use protocol::node_config::NodeConfig;
+use protocol::remote_node_info::PeerInfo;
+use protocol::signer::AsyncSigner;
+use xdr::types::{PeerAddress, PeerAddressIp, StellarMessage};
+use crate::handshake::connect;
+
+pub struct CrawlLimits {
+    /// How many hops from the seeds peers are still visited; the seeds are at depth 0.
+    pub max_depth: usize,
+    /// How many peers are visited at most, the seeds included.
+    pub max_peers: usize,
+    /// How many peers are visited at once.
+    pub jobs: usize,
+}
+
+impl Default for CrawlLimits {
+    fn default() -> Self {
+        Self { max_depth: 2, max_peers: 100, jobs: 16 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reachability {
+    Reachable,
+    Unreachable,
+    /// Learned from a peer, but beyond the limits.
+    NotCrawled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrawledNode {
+    pub address: SocketAddr,
+    /// Hops from the seeds.
+    pub depth: usize,
+    pub reachability: Reachability,
+    /// Why the handshake or asking for the peers failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub peer: Option<PeerInfo>,
+}
+
+/// `from` told us about `to`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PeerEdge {
+    pub from: SocketAddr,
+    pub to: SocketAddr,
+    /// How often `from` failed to connect to `to`, as it reported.
+    pub num_failures: u32,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct OverlayGraph {
+    pub nodes: Vec<CrawledNode>,
+    pub edges: Vec<PeerEdge>,
+}
+
+impl OverlayGraph {
+    pub fn node(&self, address: &SocketAddr) -> Option<&CrawledNode> {
+        self.nodes.iter().find(|node| node.address == *address)
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// The graph for Graphviz: reachable nodes are labelled with their node id and version,
+    /// unreachable ones are red and those not crawled dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph overlay {\n    node [shape=box, fontname=monospace];\n");
+        for node in &self.nodes {
+            let mut label = node.address.to_string();
+            if let Some(peer) = &node.peer {
+                label = format!("{}\n{}\n{}", label, peer.node_id, peer.version_str);
+            }
+            let style = match node.reachability {
+                Reachability::Reachable => "",
+                Reachability::Unreachable => ", color=red, fontcolor=red",
+                Reachability::NotCrawled => ", style=dashed",
+            };
+            let _ = writeln!(dot, "    \"{}\" [label=\"{}\"{}];", node.address, escape(&label), style);
+        }
+        for edge in &self.edges {
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\";", edge.from, edge.to);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+pub struct Crawler<'a> {
+    node_config: &'a NodeConfig,
+    signer: &'a dyn AsyncSigner,
+    limits: CrawlLimits,
+}
+
+impl<'a> Crawler<'a> {
+    pub fn new(node_config: &'a NodeConfig, signer: &'a dyn AsyncSigner, limits: CrawlLimits) -> Self {
+        Self { node_config, signer, limits }
+    }
+
+    pub async fn crawl(&self, seeds: Vec<SocketAddr>) -> OverlayGraph {
+        let mut graph = OverlayGraph::default();
+        let mut indices: HashMap<SocketAddr, usize> = HashMap::new();
+        let mut frontier = Vec::new();
+        for seed in seeds {
+            if add_node(&mut graph, &mut indices, seed, 0) {
+                frontier.push(seed);
+            }
+        }
+        let mut visited = 0;
+        let mut depth = 0;
+        while !frontier.is_empty() && depth <= self.limits.max_depth && visited < self.limits.max_peers {
+            let batch: Vec<SocketAddr> = frontier.drain(..).take(self.limits.max_peers - visited).collect();
+            visited += batch.len();
+            let mut results = stream::iter(batch)
+                .map(|address| async move { (address, self.visit(address).await) })
+                .buffered(self.limits.jobs.max(1));
+            while let Some((address, result)) = results.next().await {
+                let node = &mut graph.nodes[indices[&address]];
+                let peers = match result {
+                    Ok((peer, peers)) => {
+                        node.reachability = Reachability::Reachable;
+                        node.peer = Some(peer);
+                        peers.unwrap_or_else(|e| {
+                            node.error = Some(format!("{:#}", e));
+                            Vec::new()
+                        })
+                    }
+                    Err(e) => {
+                        node.reachability = Reachability::Unreachable;
+                        node.error = Some(format!("{:#}", e));
+                        Vec::new()
+                    }
+                };
+                info!("visited {}: {:?}, {} peers", address, node.reachability, peers.len());
+                for peer in peers {
+                    let Some(to) = socket_address(&peer) else {
+                        continue;
+                    };
+                    graph.edges.push(PeerEdge { from: address, to, num_failures: peer.num_failures });
+                    if add_node(&mut graph, &mut indices, to, depth + 1) {
+                        frontier.push(to);
+                    }
+                }
+            }
+            depth += 1;
+        }
+        graph
+    }
+
+    /// Runs the handshake with a peer and asks it for its peers;
+    /// an error of the latter does not make the peer unreachable.
+    async fn visit(&self, address: SocketAddr) -> Result<(PeerInfo, Result<Vec<PeerAddress>>)> {
+        let (mut connection, peer) = connect(address, self.node_config, self.signer).await?;
+        let peers = async {
+            connection.send_message(StellarMessage::GetPeers).await?;
+            let idle = Duration::from_millis(self.node_config.timeouts.idle_ms);
+            timeout(idle, async {
+                // stellar-core may send its peers unasked and floods other messages meanwhile
+                loop {
+                    match connection.receive_message().await? {
+                        Some(StellarMessage::Peers(peers)) => return Ok(peers.into_vec()),
+                        Some(_) => continue,
+                        None => return Err(StellarError::ExpectedMoreMessages.into()),
+                    }
+                }
+            }).await.map_err(|_| StellarError::Timeout { phase: TimeoutPhase::Idle })?
+        }.await;
+        Ok((peer, peers))
+    }
+}
+
+/// Adds a node that is not visited yet; `false` if the graph has it already.
+fn add_node(graph: &mut OverlayGraph, indices: &mut HashMap<SocketAddr, usize>, address: SocketAddr, depth: usize) -> bool {
+    match indices.entry(address) {
+        Entry::Occupied(_) => false,
+        Entry::Vacant(entry) => {
+            entry.insert(graph.nodes.len());
+            graph.nodes.push(CrawledNode { address, depth, reachability: Reachability::NotCrawled, error: None, peer: None });
+            true
+        }
+    }
+}
+
+fn socket_address(peer: &PeerAddress) -> Option<SocketAddr> {
+    let ip = match peer.ip {
+        PeerAddressIp::IPv4(ip) => IpAddr::from(ip),
+        PeerAddressIp::IPv6(ip) => IpAddr::from(ip),
+    };
+    Some(SocketAddr::new(ip, u16::try_from(peer.port).ok()?))
+}
@@ -1,25 +1,56 @@
 use crate::connection::Connection;
 use protocol::protocol::Protocol;
-use protocol::protocol::HandshakeMessageExtract;
+use protocol::session::SessionEvent;
 use anyhow::Result;
-use protocol::errors::StellarError;
-pub async fn execute_handshake<P: Protocol>(
-    connection: &mut Connection<P>,
-) -> Result<bool> {
-    let message = connection.protocol().create_hello_message();
-    connection.send(message).await?;
-    loop {
-        match connection.receive().await? {
-            Some(result)  => match connection.protocol().handle_message((&result.0, result.1))? {
-                HandshakeMessageExtract::Hello => {
-                    let auth_message = connection.protocol().create_auth_message();
-                    connection.send(auth_message).await?
-                }
-                HandshakeMessageExtract::Auth => {return Ok(true);}
-            },
-            None => {
-                return Err(StellarError::ExpectedMoreMessages.into());
-            }
-        }
+use std::net::SocketAddr;
+use std::time::Duration;
+use dryoc::rng::copy_randombytes;
+use tokio::time::timeout;
+use protocol::connection_authentication::{ConnectionAuthentication, PeerRole};
+use protocol::errors::{StellarError, TimeoutPhase};
+use protocol::node_config::NodeConfig;
+use protocol::remote_node_info::PeerInfo;
+use protocol::secret::Secret;
+use protocol::signer::AsyncSigner;
+use protocol::stellar_protocol::StellarProtocol;
+use utils::misc::{generate_nonce, get_current_u64_milliseconds};
+use xdr::compound_types::XdrArchive;
+use xdr::types::AuthenticatedMessage;
+
+/// A protocol for one connection, with a new per-connection key and an auth cert signed by `signer`.
+pub async fn create_protocol(node_config: &NodeConfig, signer: &dyn AsyncSigner, role: PeerRole) -> Result<StellarProtocol> {
+    let mut per_connection_secret_key = Secret::new([0u8; 32]);
+    copy_randombytes(per_connection_secret_key.expose_mut());
+    let now = get_current_u64_milliseconds();
+    let authentication = ConnectionAuthentication::new_async(signer, &node_config.node_info.network_id, per_connection_secret_key, now).await?;
+    Ok(StellarProtocol::new(node_config.clone(), generate_nonce(), authentication, role, Box::new(get_current_u64_milliseconds)))
+}
+
+/// Connects to a node and runs the handshake with it.
+pub async fn connect(address: SocketAddr, node_config: &NodeConfig, signer: &dyn AsyncSigner) -> Result<(Connection<StellarProtocol>, PeerInfo)> {
+    let protocol = create_protocol(node_config, signer, PeerRole::WeCalledRemote).await?;
+    let mut connection = Connection::connect(protocol, address, node_config.timeouts.clone()).await?;
+    let peer_info = execute_handshake(&mut connection).await?;
+    Ok((connection, peer_info))
+}
+
+/// Runs the handshake until it is completed and returns what the peer told about itself;
+/// the session knows from the protocol's role whether to send Hello first or to reply to the remote one.
+pub async fn execute_handshake<P>(connection: &mut Connection<P>) -> Result<P::NodeInfo>
+where
+    P: Protocol<Message = XdrArchive<AuthenticatedMessage>>,
+    P::NodeInfo: Clone,
+{
+    let handshake_timeout = Duration::from_millis(connection.timeouts().handshake_ms);
+    let event = timeout(handshake_timeout, connection.next_event())
+        .await
+        .map_err(|_| StellarError::Timeout { phase: TimeoutPhase::Handshake })??;
+    match event {
+        Some(SessionEvent::Authenticated) => match connection.peer_info() {
+            Some(peer_info) => Ok(peer_info.clone()),
+            None => Err(StellarError::NotAuthenticated.into()),
+        },
+        Some(SessionEvent::Message(_) | SessionEvent::InvalidMessage(..)) => Err(StellarError::NotAuthenticated.into()),
+        None => Err(StellarError::ExpectedMoreMessages.into()),
     }
 }
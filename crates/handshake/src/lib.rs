@@ -0,0 +1,7 @@
+pub mod codec;
+pub mod connection;
+pub mod crawler;
+pub mod handshake;
+pub mod peer_handle;
+#[cfg(unix)]
+pub mod unix_signer;
@@ -0,0 +1,201 @@
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use futures::stream::{SplitSink, SplitStream};
+use futures::{SinkExt, Stream, StreamExt};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+use tokio_util::codec::Framed;
+use anyhow::Result;
+use protocol::errors::{StellarError, VerificationError};
+use protocol::node_config::Timeouts;
+use protocol::protocol::Protocol;
+use protocol::session::{Session, SessionEvent, SessionState};
+use xdr::compound_types::XdrArchive;
+use xdr::types::{AuthenticatedMessage, StellarMessage};
+use crate::codec::AuthenticatedMessageCodec;
+use crate::connection::{idle, idle_timeout, Connection};
+
+/// Outbound messages waiting for the writer task.
+const OUTBOUND_CAPACITY: usize = 64;
+/// Verified inbound messages the caller did not take yet.
+const INBOUND_CAPACITY: usize = 64;
+
+/// What an authenticated peer sent.
+#[derive(Debug)]
+pub enum Inbound {
+    /// A verified message.
+    Message(StellarMessage),
+    /// A message that failed verification and was dropped, such as an SCP envelope with a bad
+    /// signature. The connection stays open.
+    Invalid(StellarMessage, VerificationError),
+}
+
+/// An authenticated peer served by a reader and a writer task.
+///
+/// Both tasks share the session, which holds the MAC keys, the sequence numbers and the flow control.
+/// Outbound messages are sent through [`PeerHandle::sender`], inbound messages come out of the
+/// handle as a `Stream`. The stream yields an error once if the connection failed, then ends.
+pub struct PeerHandle {
+    sender: mpsc::Sender<StellarMessage>,
+    inbound: mpsc::Receiver<Result<Inbound>>,
+    reader: JoinHandle<()>,
+    writer: JoinHandle<()>,
+}
+
+impl PeerHandle {
+    /// Spawns the tasks for a connection whose handshake is completed.
+    pub fn spawn<P>(connection: Connection<P>) -> PeerHandle
+    where
+        P: Protocol<Message = XdrArchive<AuthenticatedMessage>> + Send + 'static,
+    {
+        let (session, framed, timeouts, received_messages) = connection.into_parts();
+        let session = Arc::new(Mutex::new(session));
+        let transmit = Arc::new(Notify::new());
+        let (sink, stream) = framed.split();
+        let (sender, outbound) = mpsc::channel(OUTBOUND_CAPACITY);
+        let (inbound_sender, inbound) = mpsc::channel(INBOUND_CAPACITY.max(received_messages.len()));
+        for message in received_messages {
+            // the channel was created large enough for them
+            let _ = inbound_sender.try_send(Ok(Inbound::Message(message)));
+        }
+        let reader = tokio::spawn(read(session.clone(), stream, timeouts, transmit.clone(), inbound_sender.clone()));
+        let writer = tokio::spawn(write(session, sink, transmit, outbound, inbound_sender));
+        PeerHandle { sender, inbound, reader, writer }
+    }
+
+    /// A sender for messages to the peer. Flooded messages wait until the peer granted capacity for them.
+    pub fn sender(&self) -> mpsc::Sender<StellarMessage> {
+        self.sender.clone()
+    }
+}
+
+impl Stream for PeerHandle {
+    type Item = Result<Inbound>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inbound.poll_recv(cx)
+    }
+}
+
+impl Drop for PeerHandle {
+    fn drop(&mut self) {
+        self.reader.abort();
+        self.writer.abort();
+    }
+}
+
+type SharedSession<P> = Arc<Mutex<Session<P>>>;
+
+async fn read<P: Protocol<Message = XdrArchive<AuthenticatedMessage>>>(
+    session: SharedSession<P>,
+    mut stream: SplitStream<Framed<TcpStream, AuthenticatedMessageCodec>>,
+    timeouts: Timeouts,
+    transmit: Arc<Notify>,
+    inbound: mpsc::Sender<Result<Inbound>>,
+) {
+    loop {
+        let Ok(frame) = timeout(idle_timeout(&timeouts), stream.next()).await else {
+            let error = idle(&mut session.lock().unwrap());
+            // lets the writer see the session is closed
+            transmit.notify_one();
+            let _ = inbound.send(Err(error.into())).await;
+            return;
+        };
+        let events = match frame {
+            Some(Ok((message, payload))) => receive_frame(&session, message, payload),
+            Some(Err(e)) => Err(e.into()),
+            None => session.lock().unwrap().receive_end().map(|_| vec![]).map_err(Into::into),
+        };
+        // control messages such as SEND_MORE_EXTENDED may be waiting, and capacity may have been granted
+        transmit.notify_one();
+        match events {
+            Ok(events) if events.is_empty() && session.lock().unwrap().state() == SessionState::Closed => return,
+            Ok(events) => {
+                for event in events {
+                    let received = match event {
+                        SessionEvent::Message(message) => Inbound::Message(*message),
+                        SessionEvent::InvalidMessage(message, error) => Inbound::Invalid(*message, error),
+                        SessionEvent::Authenticated => continue,
+                    };
+                    if inbound.send(Ok(received)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = inbound.send(Err(e)).await;
+                return;
+            }
+        }
+    }
+}
+
+fn receive_frame<P: Protocol<Message = XdrArchive<AuthenticatedMessage>>>(
+    session: &SharedSession<P>,
+    message: XdrArchive<AuthenticatedMessage>,
+    payload: Vec<u8>,
+) -> Result<Vec<SessionEvent>> {
+    let mut session = session.lock().unwrap();
+    session.receive_frame(message, payload)?;
+    Ok(std::iter::from_fn(|| session.poll_event()).collect())
+}
+
+async fn write<P: Protocol<Message = XdrArchive<AuthenticatedMessage>>>(
+    session: SharedSession<P>,
+    mut sink: SplitSink<Framed<TcpStream, AuthenticatedMessageCodec>, XdrArchive<AuthenticatedMessage>>,
+    transmit: Arc<Notify>,
+    mut outbound: mpsc::Receiver<StellarMessage>,
+    inbound: mpsc::Sender<Result<Inbound>>,
+) {
+    // a message waiting for the peer to grant capacity
+    let mut pending: Option<StellarMessage> = None;
+    loop {
+        if pending.is_none() {
+            tokio::select! {
+                message = outbound.recv() => match message {
+                    Some(message) => pending = Some(message),
+                    None => return,
+                },
+                _ = transmit.notified() => {}
+            }
+        } else {
+            transmit.notified().await;
+        }
+        let frames = {
+            let mut session = session.lock().unwrap();
+            if session.state() == SessionState::Closed {
+                return;
+            }
+            let sendable = pending.as_ref().is_some_and(|message| session.can_send(message));
+            let sent = match pending.take() {
+                Some(message) if sendable => session.send(message),
+                message => {
+                    pending = message;
+                    Ok(())
+                }
+            };
+            sent.map(|_| std::iter::from_fn(|| session.poll_transmit()).collect::<Vec<_>>())
+        };
+        let result = match frames {
+            Ok(frames) => send_frames(&mut sink, frames).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            let _ = inbound.send(Err(e.into())).await;
+            return;
+        }
+    }
+}
+
+async fn send_frames(
+    sink: &mut SplitSink<Framed<TcpStream, AuthenticatedMessageCodec>, XdrArchive<AuthenticatedMessage>>,
+    frames: Vec<XdrArchive<AuthenticatedMessage>>,
+) -> Result<(), StellarError> {
+    for frame in frames {
+        sink.feed(frame).await?;
+    }
+    sink.flush().await
+}
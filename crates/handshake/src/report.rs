@@ -0,0 +1,71 @@
+//! The report of `probe`: a result per peer, printed as a table row or as a JSON line.
+use std::time::Duration;
+use serde::Serialize;
+use protocol::errors::StellarError;
+use protocol::remote_node_info::PeerInfo;
+
+#[derive(Debug, Serialize)]
+pub struct ProbeResult {
+    pub address: String,
+    pub ok: bool,
+    /// The `StellarError` the probe failed with as it is matched in code, e.g. `Verification(NetworkIdMismatch)`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The error with its context, for people.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// From the start of the TCP connect until the handshake completed or failed; `None` if the address did not resolve.
+    pub latency_ms: Option<u64>,
+    #[serde(flatten)]
+    pub peer: Option<PeerInfo>,
+}
+
+impl ProbeResult {
+    pub fn new(address: String, result: anyhow::Result<PeerInfo>, latency: Option<Duration>) -> Self {
+        let latency_ms = latency.map(|latency| latency.as_millis() as u64);
+        match result {
+            Ok(peer) => Self { address, ok: true, error: None, message: None, latency_ms, peer: Some(peer) },
+            Err(e) => Self {
+                address,
+                ok: false,
+                error: Some(match e.downcast_ref::<StellarError>() {
+                    Some(error) => format!("{:?}", error),
+                    None => format!("{:?}", e.root_cause()),
+                }),
+                message: Some(format!("{:#}", e)),
+                latency_ms,
+                peer: None,
+            },
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+pub fn print_table_header() {
+    println!("{:<28} {:<6} {:>8}  {:<56} {:<12} VERSION", "ADDRESS", "RESULT", "LATENCY", "NODE ID", "OVERLAY");
+}
+
+pub fn print_table_row(result: &ProbeResult) {
+    let latency = result.latency_ms.map_or_else(|| "-".into(), |latency| format!("{} ms", latency));
+    match (&result.peer, &result.error) {
+        (Some(peer), _) => println!(
+            "{:<28} {:<6} {:>8}  {:<56} {:<12} {}",
+            result.address,
+            "ok",
+            latency,
+            peer.node_id,
+            format!("{}..={}/{}", peer.overlay_min_version, peer.overlay_version, peer.negotiated_overlay_version),
+            peer.version_str,
+        ),
+        (None, error) => println!(
+            "{:<28} {:<6} {:>8}  {}",
+            result.address,
+            "failed",
+            latency,
+            error.as_deref().unwrap_or_default(),
+        ),
+    }
+}
@@ -0,0 +1,166 @@
+//! Signing over a Unix socket, so that the seed can stay in a process that is not reachable from the network.
+//!
+//! A request is an operation byte, `0` for the public key or `1` to sign an auth cert, followed by the big endian
+//! `u32` length of the data and the data. The data of an auth cert is the network id, the big endian `u64`
+//! expiration and the per connection Curve25519 public key; the signer hashes them itself and refuses certs
+//! for other networks than its own, so whoever reaches the socket gets nothing signed but auth certs.
+//! The response is a status byte, `0` for ok or `1` for an error, the `u32` length and the public key,
+//! the signature or the UTF-8 error message. Every request uses a connection of its own.
+use std::fs::{self, DirBuilder, Permissions};
+use std::io::{Error, ErrorKind};
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use log::{info, warn};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use protocol::connection_authentication::auth_cert_signature_data;
+use protocol::keychain::{Keychain, Uint512};
+use protocol::signer::{AsyncSigner, BoxFuture, SignerError};
+use xdr::types::{Curve25519Public, Uint256};
+
+const OPERATION_PUBLIC_KEY: u8 = 0;
+const OPERATION_SIGN_AUTH_CERT: u8 = 1;
+const STATUS_OK: u8 = 0;
+const STATUS_ERROR: u8 = 1;
+/// The network id, the expiration and the per connection public key; requests are never longer.
+const AUTH_CERT_LENGTH: usize = 32 + 8 + 32;
+const MAX_RESPONSE_LENGTH: usize = 1024;
+
+pub struct UnixSocketSigner {
+    path: PathBuf,
+    public_key: Uint256,
+}
+
+impl UnixSocketSigner {
+    /// Asks the signer at `path` for the public key.
+    pub async fn connect(path: &Path) -> Result<Self, SignerError> {
+        let response = request(path, OPERATION_PUBLIC_KEY, &[]).await?;
+        let public_key = response.try_into().map_err(|_| SignerError::InvalidResponse)?;
+        Ok(Self { path: path.to_path_buf(), public_key })
+    }
+}
+
+impl AsyncSigner for UnixSocketSigner {
+    fn public_key(&self) -> Uint256 {
+        self.public_key
+    }
+
+    fn sign_auth_cert<'a>(
+        &'a self,
+        network_id: &'a Uint256,
+        expiration: u64,
+        per_connection_pubkey: &'a Curve25519Public,
+    ) -> BoxFuture<'a, Result<Uint512, SignerError>> {
+        Box::pin(async move {
+            let cert = [&network_id[..], &expiration.to_be_bytes(), &per_connection_pubkey.key].concat();
+            let response = request(&self.path, OPERATION_SIGN_AUTH_CERT, &cert).await?;
+            response.try_into().map_err(|_| SignerError::InvalidResponse)
+        })
+    }
+}
+
+async fn request(path: &Path, operation: u8, data: &[u8]) -> Result<Vec<u8>, SignerError> {
+    let mut stream = UnixStream::connect(path).await.map_err(SignerError::Unavailable)?;
+    write_frame(&mut stream, operation, data).await.map_err(SignerError::Unavailable)?;
+    let (status, response) = read_frame(&mut stream, MAX_RESPONSE_LENGTH).await.map_err(SignerError::Unavailable)?;
+    match status {
+        STATUS_OK => Ok(response),
+        STATUS_ERROR => Err(SignerError::Refused(String::from_utf8_lossy(&response).into_owned())),
+        _ => Err(SignerError::InvalidResponse),
+    }
+}
+
+/// Signs the auth certs for `network_id` of the processes that connect to the socket at `path` until it fails.
+pub async fn serve(path: &Path, keychain: Keychain, network_id: Uint256) -> Result<()> {
+    let listener = bind_private(path)?;
+    info!("signing as {} on {}", keychain.node_id(), path.display());
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let keychain = keychain.clone();
+        tokio::spawn(async move {
+            if let Err(e) = answer(&mut stream, &keychain, &network_id).await {
+                warn!("signer request failed: {}", e);
+            }
+        });
+    }
+}
+
+/// Binds the socket in a new directory only we may enter and restricts it to us before it appears at `path`,
+/// since whoever can connect can have certs signed as us. Fails if `path` exists, e.g. as a stale socket.
+fn bind_private(path: &Path) -> std::io::Result<UnixListener> {
+    let file_name = path.file_name().ok_or_else(|| Error::new(ErrorKind::InvalidInput, "the socket path has no file name"))?;
+    let directory = path.with_file_name(format!(".{}.{}", file_name.to_string_lossy(), std::process::id()));
+    DirBuilder::new().mode(0o700).create(&directory)?;
+    let staged = directory.join("socket");
+    let result = UnixListener::bind(&staged).and_then(|listener| {
+        fs::set_permissions(&staged, Permissions::from_mode(0o600))?;
+        // unlike a rename, a link does not replace what is at `path`
+        fs::hard_link(&staged, path).map_err(|e| match e.kind() {
+            ErrorKind::AlreadyExists => already_exists(path),
+            _ => e,
+        })?;
+        Ok(listener)
+    });
+    let _ = fs::remove_file(&staged);
+    let _ = fs::remove_dir(&directory);
+    result
+}
+
+fn already_exists(path: &Path) -> Error {
+    let hint = match std::os::unix::net::UnixStream::connect(path) {
+        Ok(_) => "a signer is listening on it already",
+        Err(_) => "remove it if it is left over from a signer that is not running anymore",
+    };
+    Error::new(ErrorKind::AlreadyExists, format!("{} exists, {}", path.display(), hint))
+}
+
+async fn answer(stream: &mut UnixStream, keychain: &Keychain, network_id: &Uint256) -> std::io::Result<()> {
+    let (operation, data) = read_frame(stream, AUTH_CERT_LENGTH).await?;
+    let response = match operation {
+        OPERATION_PUBLIC_KEY => Ok(keychain.persistent_public_key().to_vec()),
+        OPERATION_SIGN_AUTH_CERT => sign_auth_cert(keychain, network_id, &data).map(|signature| signature.to_vec()),
+        _ => Err(format!("unknown operation {}", operation)),
+    };
+    match response {
+        Ok(response) => write_frame(stream, STATUS_OK, &response).await,
+        Err(message) => {
+            warn!("refused to sign: {}", message);
+            write_frame(stream, STATUS_ERROR, message.as_bytes()).await
+        }
+    }
+}
+
+/// Signs the auth cert `cert` describes, if it is one for our network.
+fn sign_auth_cert(keychain: &Keychain, network_id: &Uint256, cert: &[u8]) -> Result<Uint512, String> {
+    if cert.len() != AUTH_CERT_LENGTH {
+        return Err(format!("an auth cert takes {} bytes, not {}", AUTH_CERT_LENGTH, cert.len()));
+    }
+    let (cert_network_id, rest) = cert.split_at(32);
+    let (expiration, per_connection_pubkey) = rest.split_at(8);
+    if cert_network_id != network_id {
+        return Err("the auth cert is for another network".into());
+    }
+    let expiration = u64::from_be_bytes(expiration.try_into().expect("split at 8 bytes"));
+    let per_connection_pubkey = Curve25519Public { key: per_connection_pubkey.try_into().expect("the rest of the length checked") };
+    Ok(keychain.sign(auth_cert_signature_data(network_id, expiration, &per_connection_pubkey)))
+}
+
+async fn write_frame(stream: &mut (impl AsyncWrite + Unpin), kind: u8, data: &[u8]) -> std::io::Result<()> {
+    let mut frame = vec![kind];
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(data);
+    stream.write_all(&frame).await?;
+    stream.flush().await
+}
+
+async fn read_frame(stream: &mut (impl AsyncRead + Unpin), max_length: usize) -> std::io::Result<(u8, Vec<u8>)> {
+    let kind = stream.read_u8().await?;
+    let length = stream.read_u32().await? as usize;
+    if length > max_length {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{} bytes exceed the limit of {}", length, max_length)));
+    }
+    let mut data = vec![0u8; length];
+    stream.read_exact(&mut data).await?;
+    Ok((kind, data))
+}
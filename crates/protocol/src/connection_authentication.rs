@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
 
-use super::keychain::Keychain;
+use crate::secret::Secret;
+use crate::signer::{AsyncSigner, Signer, SignerError};
 use dryoc::classic::crypto_core::crypto_scalarmult_base;
 use xdr::types::*;
 use dryoc::classic::crypto_sign::crypto_sign_verify_detached;
@@ -13,44 +14,55 @@ use crate::constants::{PUBLIC_KEY_LENGTH, SEED_LENGTH, SHA256_LENGTH};
 
 #[derive(Debug)]
 pub struct ConnectionAuthentication {
-    keychain: Keychain,
+    /// Our node id; the key itself stays with the signer.
+    public_key: Uint256,
     network_id: xdr::types::Uint256,
-    per_connection_seckey: Curve25519Secret,
+    per_connection_seckey: Secret<Uint256>,
     per_connection_pubkey: xdr::types::Curve25519Public,
     /// We don't need to store them for handshake process, but if we want to send more and receive more messages, we need to store them.
-    we_called_remote_keys: HashMap<Uint256, Vec<u8>>,
-    us_called_remote_keys: HashMap<Uint256, Vec<u8>>,
-    auth_cert: Option<AuthCert>,
-    auth_cert_expiration: u64,
+    we_called_remote_keys: HashMap<Uint256, Secret<Vec<u8>>>,
+    remote_called_us_keys: HashMap<Uint256, Secret<Vec<u8>>>,
+    auth_cert: AuthCert,
 }
 
 impl ConnectionAuthentication {
-    // value taken from original code
-    const  AUTH_EXPIRATION_LIMIT: u64 = 3600000;
-    pub fn new(keypair: Keychain, network_id: impl AsRef<[u8]>, per_connection_secret_key: [u8; SEED_LENGTH]) -> Self {
+    // value taken from original code, in seconds
+    const  AUTH_EXPIRATION_LIMIT: u64 = 3600;
+    /// Signs the auth cert of the connection right away, it stays valid for longer than any handshake.
+    pub fn new(signer: &dyn Signer, network_id: impl AsRef<[u8]>, per_connection_secret_key: Secret<[u8; SEED_LENGTH]>, milisec: u64) -> Result<Self, SignerError> {
+        let mut authentication = Self::unsigned(signer.public_key(), network_id, per_connection_secret_key, milisec);
+        let signature = signer.sign(&auth_cert_signature_data(&authentication.network_id, authentication.auth_cert.expiration, &authentication.per_connection_pubkey))?;
+        authentication.auth_cert.sig = Signature::new(signature.to_vec()).unwrap();
+        Ok(authentication)
+    }
+    /// Like [`ConnectionAuthentication::new`] with a signer we have to wait for.
+    pub async fn new_async(signer: &dyn AsyncSigner, network_id: impl AsRef<[u8]>, per_connection_secret_key: Secret<[u8; SEED_LENGTH]>, milisec: u64) -> Result<Self, SignerError> {
+        let mut authentication = Self::unsigned(signer.public_key(), network_id, per_connection_secret_key, milisec);
+        let signature = signer.sign_auth_cert(&authentication.network_id, authentication.auth_cert.expiration, &authentication.per_connection_pubkey).await?;
+        authentication.auth_cert.sig = Signature::new(signature.to_vec()).unwrap();
+        Ok(authentication)
+    }
+    fn unsigned(public_key: Uint256, network_id: impl AsRef<[u8]>, per_connection_secret_key: Secret<[u8; SEED_LENGTH]>, milisec: u64) -> Self {
         let hashed_network_id = create_sha256(network_id.as_ref());
         let mut public_key_ecdh = [0u8; PUBLIC_KEY_LENGTH];
-        crypto_scalarmult_base(&mut public_key_ecdh, &per_connection_secret_key);
+        crypto_scalarmult_base(&mut public_key_ecdh, per_connection_secret_key.expose());
+        let per_connection_pubkey = Curve25519Public{key: public_key_ecdh};
         Self {
             we_called_remote_keys: Default::default(),
-            us_called_remote_keys: Default::default(),
-            keychain: keypair,
+            remote_called_us_keys: Default::default(),
+            public_key,
             network_id: hashed_network_id,
-            per_connection_pubkey: Curve25519Public{key: public_key_ecdh},
-            per_connection_seckey: Curve25519Secret{key: per_connection_secret_key },
-            auth_cert: None,
-            auth_cert_expiration: 0
+            per_connection_seckey: per_connection_secret_key,
+            auth_cert: AuthCert {
+                pubkey: per_connection_pubkey.clone(),
+                expiration: milisec / 1000 + Self::AUTH_EXPIRATION_LIMIT,
+                sig: Signature::new(Vec::new()).unwrap(),
+            },
+            per_connection_pubkey,
         }
     }
-    pub fn auth_cert(&mut self, milisec: u64) -> &AuthCert {
-        let cert = match self.auth_cert.take() {
-            Some(cert) if self.auth_cert_expiration >= milisec => cert,
-            _ => {
-                self.create_auth_cert_from_milisec(milisec)
-            },
-        };
-        self.auth_cert = Some(cert);
-        self.auth_cert.as_ref().unwrap()
+    pub fn auth_cert(&self) -> &AuthCert {
+        &self.auth_cert
     }
     pub fn verify_cert(&self,
                        time: u64,
@@ -61,56 +73,61 @@ impl ConnectionAuthentication {
         if expiration < (time / 1000) {
             return Err(AuthenticationError::VerificationCertExpired)
         }
-        let signature_data = [self.network_id.as_slice(), EnvelopeType::EnvelopeTypeAuth.to_xdr().as_slice(), &cert.expiration.to_be_bytes(), &cert.pubkey.key].concat();
-        let message = create_sha256(&signature_data);
+        let message = auth_cert_signature_data(&self.network_id, cert.expiration, &cert.pubkey);
 
-        let mut sig = [0u8; 64];
-        sig.copy_from_slice(cert.sig.get_vec());
+        let sig = <[u8; 64]>::try_from(cert.sig.get_vec().as_slice()).map_err(|_| AuthenticationError::VerificationSignature)?;
         crypto_sign_verify_detached(&sig, &message, remote_public_key).map_err(|_| AuthenticationError::VerificationSignature)
     }
-    /// `we_called_remote` parameter can be replaced with enum for a better readability and data-driven approach
-    pub fn mac_key(&mut self,
-                          local_nonce: &Uint256,
-                          remote_nonce: &Uint256,
-                          remote_public_key_ecdh: &Uint256,
-        we_called_remote: bool
-    ) -> Vec<u8> {
-        let message = if we_called_remote {
-            [&[0], local_nonce.as_ref(), remote_nonce.as_ref(), &[1]].concat()
-        } else {
-            [&[1], remote_nonce.as_ref(), local_nonce.as_ref(), &[1]].concat()
+    pub fn sending_mac_key(&mut self,
+                           local_nonce: &Uint256,
+                           remote_nonce: &Uint256,
+                           remote_public_key_ecdh: &Uint256,
+                           role: PeerRole
+    ) -> Secret<Vec<u8>> {
+        let prefix = match role {
+            PeerRole::WeCalledRemote => 0,
+            PeerRole::RemoteCalledUs => 1,
         };
-        let shared_key = self.shared_key(remote_public_key_ecdh, we_called_remote);
-        create_sha256_hmac(&message, &shared_key)
+        let message = [&[prefix], local_nonce.as_ref(), remote_nonce.as_ref(), &[1]].concat();
+        let shared_key = self.shared_key(remote_public_key_ecdh, role);
+        Secret::new(create_sha256_hmac(&message, shared_key.expose()))
     }
-    fn shared_key(&mut self, remote_public_key: &Uint256, we_called_remote: bool) -> Vec<u8> {
-        let keys_storage = if we_called_remote {&mut self.we_called_remote_keys} else {&mut self.us_called_remote_keys};
+    pub fn receiving_mac_key(&mut self,
+                             local_nonce: &Uint256,
+                             remote_nonce: &Uint256,
+                             remote_public_key_ecdh: &Uint256,
+                             role: PeerRole
+    ) -> Secret<Vec<u8>> {
+        let prefix = match role {
+            PeerRole::WeCalledRemote => 1,
+            PeerRole::RemoteCalledUs => 0,
+        };
+        let message = [&[prefix], remote_nonce.as_ref(), local_nonce.as_ref(), &[1]].concat();
+        let shared_key = self.shared_key(remote_public_key_ecdh, role);
+        Secret::new(create_sha256_hmac(&message, shared_key.expose()))
+    }
+    /// The initiator's ECDH public key always comes first, so both sides derive the same key.
+    fn shared_key(&mut self, remote_public_key: &Uint256, role: PeerRole) -> Secret<Vec<u8>> {
+        let keys_storage = match role {
+            PeerRole::WeCalledRemote => &mut self.we_called_remote_keys,
+            PeerRole::RemoteCalledUs => &mut self.remote_called_us_keys,
+        };
         if let Some(shared_key) = keys_storage.get(remote_public_key.as_ref()) {
             return shared_key.clone();
         }
-        let mut shared_secret_key = [0u8; dryoc::constants::CRYPTO_SCALARMULT_BYTES];
-        dryoc::classic::crypto_core::crypto_scalarmult(&mut shared_secret_key, &self.per_connection_seckey.key, remote_public_key);
-        let message_to_sign = [&shared_secret_key, &self.per_connection_pubkey.key, remote_public_key.as_ref()].concat();
+        let mut shared_secret_key = Secret::new([0u8; dryoc::constants::CRYPTO_SCALARMULT_BYTES]);
+        dryoc::classic::crypto_core::crypto_scalarmult(shared_secret_key.expose_mut(), self.per_connection_seckey.expose(), remote_public_key);
+        let message_to_sign = Secret::new(match role {
+            PeerRole::WeCalledRemote => [shared_secret_key.expose(), &self.per_connection_pubkey.key, remote_public_key.as_ref()].concat(),
+            PeerRole::RemoteCalledUs => [shared_secret_key.expose(), remote_public_key.as_ref(), &self.per_connection_pubkey.key].concat(),
+        });
         let zero_salt = [0u8; SHA256_LENGTH];
-        let hmac = create_sha256_hmac(&message_to_sign, &zero_salt);
+        let hmac = Secret::new(create_sha256_hmac(message_to_sign.expose(), &zero_salt));
         keys_storage.insert(*remote_public_key, hmac.clone());
         hmac
     }
-    fn create_auth_cert_from_milisec(&mut self, milisec: u64) -> AuthCert {
-        self.auth_cert_expiration = milisec + Self::AUTH_EXPIRATION_LIMIT;
-        let bytes_expiration = self.auth_cert_expiration.to_be_bytes();
-        let signature_data = [self.network_id.as_slice(), EnvelopeType::EnvelopeTypeAuth.to_xdr().as_slice(), &bytes_expiration, &self.per_connection_pubkey.key].concat();
-        let hashed_signature_data = create_sha256(&signature_data);
-        let signed = self.keychain.sign(hashed_signature_data);
-        let sig = Signature::new(signed.to_vec()).unwrap();
-        AuthCert {
-            pubkey: self.per_connection_pubkey.clone(),
-            expiration: self.auth_cert_expiration,
-            sig
-        }
-    }
-    pub fn keychain(&self) -> &Keychain {
-        &self.keychain
+    pub fn public_key(&self) -> &Uint256 {
+        &self.public_key
     }
     pub fn network_id(&self) -> Uint256 {
         self.network_id
@@ -118,6 +135,18 @@ impl ConnectionAuthentication {
 }
 
 
+/// What an auth cert signs: the hash of the network id, the envelope type, the expiration and the per connection key.
+pub fn auth_cert_signature_data(network_id: &Uint256, expiration: u64, per_connection_pubkey: &Curve25519Public) -> Uint256 {
+    let signature_data = [network_id.as_slice(), EnvelopeType::EnvelopeTypeAuth.to_xdr().as_slice(), &expiration.to_be_bytes(), &per_connection_pubkey.key].concat();
+    create_sha256(&signature_data)
+}
+
+/// Which side opened the TCP connection; it decides how the MAC keys are derived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerRole {
+    WeCalledRemote,
+    RemoteCalledUs,
+}
 
 #[derive(Error, Debug)]
 pub enum AuthenticationError {
@@ -127,3 +156,35 @@ pub enum AuthenticationError {
     VerificationSignature
 }
 
+
+#[cfg(test)]
+mod tests {
+    use crate::keychain::Keychain;
+    use super::*;
+
+    /// Whether `debug` shows the first bytes of `key` as `{:?}` formats a byte array.
+    fn shows(debug: &str, key: &[u8]) -> bool {
+        debug.contains(format!("{:?}", &key[..8]).trim_matches(['[', ']']))
+    }
+
+    #[test]
+    fn debug_redacts_the_keys() {
+        let seed: [u8; SEED_LENGTH] = std::array::from_fn(|index| index as u8 + 1);
+        let per_connection_secret_key: [u8; SEED_LENGTH] = std::array::from_fn(|index| index as u8 + 101);
+        let keychain = Keychain::from(&seed);
+        let mut authentication = ConnectionAuthentication::new(&keychain, "Test Network", Secret::new(per_connection_secret_key), 0).unwrap();
+        let mut remote_public_key = [0u8; 32];
+        crypto_scalarmult_base(&mut remote_public_key, &[9; 32]);
+        let sending = authentication.sending_mac_key(&[1; 32], &[2; 32], &remote_public_key, PeerRole::WeCalledRemote);
+        let receiving = authentication.receiving_mac_key(&[1; 32], &[2; 32], &remote_public_key, PeerRole::WeCalledRemote);
+
+        let debug = format!("{:?}", authentication);
+        assert!(debug.contains("Secret([REDACTED])"), "{}", debug);
+        for key in [&seed[..], &per_connection_secret_key, sending.expose(), receiving.expose()] {
+            assert!(!shows(&debug, key), "{}", debug);
+        }
+        // the check would see them
+        assert!(shows(&format!("{:?}", per_connection_secret_key), &per_connection_secret_key));
+        assert!(shows(&debug, &authentication.per_connection_pubkey.key));
+    }
+}
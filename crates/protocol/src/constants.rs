@@ -1,4 +1,4 @@
 pub const SHA256_LENGTH: usize = 32;
 pub const SEED_LENGTH: usize = 32;
 pub const ED25519_SECRET_KEY_BYTE_LENGTH: usize = PUBLIC_KEY_LENGTH + SEED_LENGTH;
-pub const PUBLIC_KEY_LENGTH: usize = 32;
\ No newline at end of file
+pub const PUBLIC_KEY_LENGTH: usize = 32;
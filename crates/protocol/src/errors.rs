@@ -2,6 +2,8 @@ use std::fmt::{Debug, Display, Formatter};
 use thiserror::Error;
 use std::{fmt, io};
 use xdr::{DecodeError};
+use xdr::types::ErrorCode;
+use crate::session::SessionState;
 use crate::connection_authentication::AuthenticationError;
 use crate::prelude::W;
 
@@ -11,6 +13,27 @@ pub enum VerificationError {
     SequenceMismatch,
     #[error("Mac key verification failed")]
     MacKey,
+    #[error("Remote node is on a different network")]
+    NetworkIdMismatch,
+    #[error("Remote overlay versions {remote_min}..={remote_max} do not overlap with ours")]
+    OverlayVersionMismatch { remote_min: u32, remote_max: u32 },
+    #[error("Remote node has our own node id")]
+    ConnectedToSelf,
+    #[error("Remote node sent our own nonce")]
+    NonceReused,
+    #[error("Remote node is in the deny list")]
+    PeerDenied,
+    #[error("Remote node is not in the allow list")]
+    PeerNotAllowed,
+    #[error("SCP envelope of {node_id} for slot {slot_index} has an invalid signature")]
+    ScpSignature { node_id: String, slot_index: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutPhase {
+    Connect,
+    Handshake,
+    Idle,
 }
 
 #[derive(Debug, Error)]
@@ -23,7 +46,27 @@ pub enum StellarError {
     IOError(#[from] io::Error),
     ConnectionResetByPeer,
     ExpectedMoreMessages,
+    #[error("Messages other than Hello and Auth require a completed handshake")]
+    NotAuthenticated,
+    #[error("Hello or Auth received after the handshake")]
+    UnexpectedMessage,
+    #[error("{message} is not allowed in state {state:?}")]
+    IllegalTransition { state: SessionState, message: &'static str },
+    #[error("The session is closed")]
+    SessionClosed,
+    #[error("The peer has not granted capacity for this message")]
+    NoCapacity,
+    #[error("The peer sent more than the capacity we granted")]
+    FlowControlViolation,
+    #[error("The peer sent SEND_MORE, but only SEND_MORE_EXTENDED is used once byte based flow control is requested")]
+    UnsupportedSendMore,
+    #[error("Timed out during {phase:?}")]
+    Timeout { phase: TimeoutPhase },
+    #[error("Peer reported {code:?}: {message}")]
+    PeerError { code: ErrorCode, message: String },
     Verification(#[from] VerificationError),
+    #[error("Invalid configuration value for {field}: {message}")]
+    InvalidConfig { field: String, message: String },
 }
 
 impl fmt::Display for W<DecodeError> {
@@ -38,6 +81,18 @@ impl fmt::Display for W<DecodeError> {
             DecodeError::InvalidXdrArchiveLength { at_position } => {
                 write!(f, "Invalid length encoding in XDR stream at position {}.", at_position)
             }
+            DecodeError::RecordTooLarge { requested_length, allowed_length } => {
+                write!(f, "Record of {} bytes exceeds the maximum record size of {} bytes.", requested_length, allowed_length)
+            }
+            DecodeError::TooManyFragments { at_position, allowed_fragments } => {
+                write!(f, "Record has more than {} fragments at position {}.", allowed_fragments, at_position)
+            }
+            DecodeError::EmptyFragment { at_position } => {
+                write!(f, "Empty record fragment that is not the last at position {}.", at_position)
+            }
+            DecodeError::NestingTooDeep { at_position, allowed_depth } => {
+                write!(f, "Values nest deeper than {} levels at position {}.", allowed_depth, at_position)
+            }
             DecodeError::ExceedsMaximumLength { requested_length, allowed_length } => {
                 write!(f, "Exceeds Maximum Length requested: {}, allowed: {} .", requested_length,  allowed_length)
             }
@@ -0,0 +1,260 @@
+use xdr::types::{SendMoreExtended, StellarMessage};
+use xdr::XdrCodec;
+
+use crate::errors::StellarError;
+
+/// Capacity we grant the peer, the defaults of stellar-core.
+pub const PEER_FLOOD_READING_CAPACITY: u32 = 200;
+pub const PEER_FLOOD_READING_CAPACITY_BYTES: u32 = 300_000;
+/// We grant capacity again once the peer used this much of it.
+pub const FLOW_CONTROL_SEND_MORE_BATCH_SIZE: u32 = 40;
+pub const FLOW_CONTROL_SEND_MORE_BATCH_SIZE_BYTES: u32 = 100_000;
+
+/// Message and byte based flow control of the overlay (SEND_MORE_EXTENDED).
+///
+/// Only flooded messages count: transactions, SCP messages, flood adverts and demands, each with
+/// its [`body_size`].
+/// The peer grants us capacity to send them, and we grant it capacity in batches while we read them.
+/// Our Auth requests byte based flow control, so a peer granting messages only with the legacy
+/// SEND_MORE is rejected instead of leaving us waiting for capacity.
+pub struct FlowControl {
+    /// What the peer still allows us to send.
+    outbound_messages: u64,
+    outbound_bytes: u64,
+    /// What we still allow the peer to send.
+    inbound_messages: u64,
+    inbound_bytes: u64,
+    /// Read since we last granted capacity.
+    processed_messages: u32,
+    processed_bytes: u32,
+    /// Capacity to grant that is not sent yet.
+    pending: Option<SendMoreExtended>,
+}
+
+impl FlowControl {
+    pub fn new() -> Self {
+        Self {
+            outbound_messages: 0,
+            outbound_bytes: 0,
+            inbound_messages: 0,
+            inbound_bytes: 0,
+            processed_messages: 0,
+            processed_bytes: 0,
+            pending: None,
+        }
+    }
+
+    /// Flags of our Auth message, requesting byte based flow control.
+    pub fn auth_flags() -> i32 {
+        xdr::constants::AUTH_MSG_FLAG_FLOW_CONTROL_BYTES_REQUESTED
+    }
+
+    /// Bytes a message takes from the capacity, its XDR without the `MessageType`: `msgBodySize` of stellar-core.
+    pub fn body_size(message: &StellarMessage) -> u64 {
+        // the MessageType is an int
+        (message.to_xdr().len() - 4) as u64
+    }
+
+    pub fn is_flow_controlled(message: &StellarMessage) -> bool {
+        matches!(
+            message,
+            StellarMessage::Transaction(_) | StellarMessage::ScpMessage(_) | StellarMessage::FloodAdvert(_) | StellarMessage::FloodDemand(_)
+        )
+    }
+
+    /// Starts flow control once authenticated by granting the peer its initial capacity.
+    pub fn start(&mut self) {
+        self.grant(PEER_FLOOD_READING_CAPACITY, PEER_FLOOD_READING_CAPACITY_BYTES);
+    }
+
+    fn grant(&mut self, num_messages: u32, num_bytes: u32) {
+        self.inbound_messages += num_messages as u64;
+        self.inbound_bytes += num_bytes as u64;
+        let pending = self.pending.get_or_insert(SendMoreExtended { num_messages: 0, num_bytes: 0 });
+        pending.num_messages += num_messages;
+        pending.num_bytes += num_bytes;
+    }
+
+    /// SEND_MORE_EXTENDED we owe the peer, if any.
+    pub fn take_send_more(&mut self) -> Option<StellarMessage> {
+        self.pending.take().map(StellarMessage::SendMoreExtended)
+    }
+
+    /// Whether the peer granted enough capacity to send `message` now.
+    pub fn can_send(&self, message: &StellarMessage) -> bool {
+        !Self::is_flow_controlled(message)
+            || (self.outbound_messages > 0 && self.outbound_bytes >= Self::body_size(message))
+    }
+
+    /// Takes the capacity `message` needs from what the peer granted us.
+    pub fn reserve(&mut self, message: &StellarMessage) -> Result<(), StellarError> {
+        if !Self::is_flow_controlled(message) {
+            return Ok(());
+        }
+        if !self.can_send(message) {
+            return Err(StellarError::NoCapacity);
+        }
+        self.outbound_messages -= 1;
+        self.outbound_bytes -= Self::body_size(message);
+        Ok(())
+    }
+
+    /// Accounts a message received from the peer.
+    pub fn received(&mut self, message: &StellarMessage) -> Result<(), StellarError> {
+        match message {
+            StellarMessage::SendMoreExtended(send_more) => {
+                self.outbound_messages += send_more.num_messages as u64;
+                self.outbound_bytes += send_more.num_bytes as u64;
+            }
+            StellarMessage::SendMore(_) => return Err(StellarError::UnsupportedSendMore),
+            message if Self::is_flow_controlled(message) => {
+                let size = Self::body_size(message);
+                if self.inbound_messages == 0 || self.inbound_bytes < size {
+                    return Err(StellarError::FlowControlViolation);
+                }
+                self.inbound_messages -= 1;
+                self.inbound_bytes -= size;
+                self.processed_messages += 1;
+                self.processed_bytes += size as u32;
+                if self.processed_messages >= FLOW_CONTROL_SEND_MORE_BATCH_SIZE
+                    || self.processed_bytes >= FLOW_CONTROL_SEND_MORE_BATCH_SIZE_BYTES {
+                    self.grant(self.processed_messages, self.processed_bytes);
+                    self.processed_messages = 0;
+                    self.processed_bytes = 0;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl Default for FlowControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use xdr::compound_types::VarArray;
+    use xdr::types::{FloodDemand, SendMore};
+    use super::*;
+
+    /// A flooded message with a body of `4 + 32 * hashes` bytes.
+    fn flooded(hashes: usize) -> StellarMessage {
+        StellarMessage::FloodDemand(FloodDemand { tx_hashes: VarArray::new(vec![[0; 32]; hashes]).unwrap() })
+    }
+
+    fn send_more(num_messages: u32, num_bytes: u32) -> StellarMessage {
+        StellarMessage::SendMoreExtended(SendMoreExtended { num_messages, num_bytes })
+    }
+
+    #[test]
+    fn sends_only_what_the_peer_granted() {
+        let mut flow_control = FlowControl::new();
+        let message = flooded(1);
+        assert!(flow_control.can_send(&StellarMessage::GetPeers));
+        assert!(matches!(flow_control.reserve(&message), Err(StellarError::NoCapacity)));
+
+        flow_control.received(&send_more(2, 36 + 35)).unwrap();
+        flow_control.reserve(&message).unwrap();
+        // a message is left, but one byte short
+        assert!(!flow_control.can_send(&message));
+        assert!(matches!(flow_control.reserve(&message), Err(StellarError::NoCapacity)));
+
+        flow_control.received(&send_more(0, 1)).unwrap();
+        flow_control.reserve(&message).unwrap();
+        assert!(matches!(flow_control.reserve(&message), Err(StellarError::NoCapacity)));
+        flow_control.reserve(&StellarMessage::GetPeers).unwrap();
+    }
+
+    #[test]
+    fn only_send_more_extended_grants_capacity() {
+        let mut flow_control = FlowControl::new();
+        flow_control.start();
+        flow_control.received(&flooded(1)).unwrap();
+        flow_control.received(&StellarMessage::GetPeers).unwrap();
+        assert!(!flow_control.can_send(&flooded(1)));
+        assert!(matches!(
+            flow_control.received(&StellarMessage::SendMore(SendMore { num_messages: 10 })),
+            Err(StellarError::UnsupportedSendMore)
+        ));
+        assert!(!flow_control.can_send(&flooded(1)));
+    }
+
+    #[test]
+    fn rejects_messages_beyond_the_granted_capacity() {
+        let mut flow_control = FlowControl::new();
+        assert!(matches!(flow_control.received(&flooded(1)), Err(StellarError::FlowControlViolation)));
+        // not flooded, so not counted
+        flow_control.received(&StellarMessage::GetPeers).unwrap();
+
+        let mut flow_control = FlowControl::new();
+        flow_control.grant(1, 1_000);
+        flow_control.received(&flooded(0)).unwrap();
+        assert!(matches!(flow_control.received(&flooded(0)), Err(StellarError::FlowControlViolation)));
+
+        let mut flow_control = FlowControl::new();
+        flow_control.grant(2, 100);
+        flow_control.received(&flooded(2)).unwrap();
+        // 36 bytes, 32 left
+        assert!(matches!(flow_control.received(&flooded(1)), Err(StellarError::FlowControlViolation)));
+    }
+
+    #[test]
+    fn counts_the_body_without_the_message_type() {
+        let message = flooded(1);
+        assert_eq!(message.to_xdr().len(), 40);
+        assert_eq!(FlowControl::body_size(&message), 36);
+
+        // a peer may use exactly the bytes it was granted
+        let mut flow_control = FlowControl::new();
+        flow_control.grant(3, 2 * 36);
+        flow_control.received(&message).unwrap();
+        flow_control.received(&message).unwrap();
+        assert!(matches!(flow_control.received(&flooded(0)), Err(StellarError::FlowControlViolation)));
+
+        // and so may we
+        let mut flow_control = FlowControl::new();
+        flow_control.received(&send_more(2, 2 * 36)).unwrap();
+        flow_control.reserve(&message).unwrap();
+        flow_control.reserve(&message).unwrap();
+    }
+
+    #[test]
+    fn grants_initial_capacity_on_start() {
+        let mut flow_control = FlowControl::new();
+        assert_eq!(flow_control.take_send_more(), None);
+        flow_control.start();
+        assert_eq!(flow_control.take_send_more(), Some(send_more(PEER_FLOOD_READING_CAPACITY, PEER_FLOOD_READING_CAPACITY_BYTES)));
+        assert_eq!(flow_control.take_send_more(), None);
+    }
+
+    #[test]
+    fn regrants_after_a_batch_of_messages() {
+        let mut flow_control = FlowControl::new();
+        flow_control.start();
+        flow_control.take_send_more();
+        for _ in 1..FLOW_CONTROL_SEND_MORE_BATCH_SIZE {
+            flow_control.received(&flooded(0)).unwrap();
+        }
+        assert_eq!(flow_control.take_send_more(), None);
+        flow_control.received(&flooded(0)).unwrap();
+        assert_eq!(flow_control.take_send_more(), Some(send_more(FLOW_CONTROL_SEND_MORE_BATCH_SIZE, 4 * FLOW_CONTROL_SEND_MORE_BATCH_SIZE)));
+    }
+
+    #[test]
+    fn regrants_after_a_batch_of_bytes() {
+        let mut flow_control = FlowControl::new();
+        flow_control.start();
+        flow_control.take_send_more();
+        // 782 * 32 + 4 = 25_028 bytes
+        for _ in 0..3 {
+            flow_control.received(&flooded(782)).unwrap();
+        }
+        assert_eq!(flow_control.take_send_more(), None);
+        flow_control.received(&flooded(782)).unwrap();
+        assert_eq!(flow_control.take_send_more(), Some(send_more(4, 4 * 25_028)));
+    }
+}
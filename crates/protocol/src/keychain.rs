@@ -1,55 +1,63 @@
-use data_encoding::BASE32;
-use dryoc::classic::crypto_sign::{crypto_sign_detached, crypto_sign_seed_keypair};
-use dryoc::dryocbox::ByteArray;
+use std::path::{Path, PathBuf};
+use dryoc::classic::crypto_sign::{crypto_sign_detached, crypto_sign_seed_keypair_inplace};
+use utils::strkey::{decode_seed, encode_public_key, StrKeyError};
 use xdr::types::{PublicKey, Uint256};
-use crate::constants::{ED25519_SECRET_KEY_BYTE_LENGTH, SEED_LENGTH};
+use crate::constants::{ED25519_SECRET_KEY_BYTE_LENGTH, PUBLIC_KEY_LENGTH, SEED_LENGTH};
+use crate::secret::Secret;
 
 pub type Uint512 = [u8; 64];
 
 #[derive(Debug, Clone)]
 pub struct Keychain {
     persistent_public_key: PublicKey,
-    signing_key: [u8; ED25519_SECRET_KEY_BYTE_LENGTH]
+    signing_key: Secret<[u8; ED25519_SECRET_KEY_BYTE_LENGTH]>
 }
 
 impl Keychain {
     pub fn sign(&self, message: impl AsRef<[u8]>) -> Uint512 {
         let mut signature = [0u8; ED25519_SECRET_KEY_BYTE_LENGTH];
-        crypto_sign_detached(&mut signature, message.as_ref(), &self.signing_key).unwrap_or_default();
+        crypto_sign_detached(&mut signature, message.as_ref(), self.signing_key.expose()).unwrap_or_default();
         signature
     }
     pub fn persistent_public_key(&self) -> &Uint256 {
         self.persistent_public_key.as_binary()
     }
+
+    /// Our node id as a `G...` strkey.
+    pub fn node_id(&self) -> String {
+        encode_public_key(self.persistent_public_key())
+    }
+
+    /// The keychain of an `S...` seed, checking its version byte and checksum.
+    pub fn from_encoded_seed(seed: &str) -> Result<Self, KeychainError> {
+        Keychain::try_from(seed.trim())
+    }
+
+    /// Reads an `S...` seed from a file such as the one `keygen --output` writes.
+    pub fn from_seed_file(path: &Path) -> Result<Self, KeychainError> {
+        let seed = std::fs::read_to_string(path)
+            .map(Secret::new)
+            .map_err(|source| KeychainError::SeedFile { path: path.to_path_buf(), source })?;
+        Keychain::from_encoded_seed(seed.expose())
+    }
 }
 
 impl TryFrom<&str> for Keychain {
     type Error = KeychainError;
     fn try_from(key: &str) -> Result<Self, Self::Error> {
-        let decoded = BASE32.decode(key.as_bytes()).unwrap();
-        let _version_byte = decoded[0];
-        let payload = &decoded[..decoded.len()-2];
-        let data = &payload[1..];
-        if data.len() != SEED_LENGTH {
-            return Err(KeychainError::WrongLength {
-                expected: SEED_LENGTH as u32,
-                actual: data.len() as u32
-            });
-        }
-        let mut seed = [0u8; SEED_LENGTH];
-        seed.copy_from_slice(data);
-        Ok(Keychain::from(&seed))
+        let seed = Secret::new(decode_seed(key)?);
+        Ok(Keychain::from(seed.expose()))
     }
 }
 
 impl From<&[u8; SEED_LENGTH]> for Keychain {
     fn from(seed: &[u8; SEED_LENGTH]) -> Self {
-        let public_key = crypto_sign_seed_keypair(seed).0;
-        let mut signing_key = [0u8; ED25519_SECRET_KEY_BYTE_LENGTH];
-        signing_key[..SEED_LENGTH].copy_from_slice(seed);
-        signing_key[SEED_LENGTH..].copy_from_slice(&public_key);
+        let mut public_key = [0u8; PUBLIC_KEY_LENGTH];
+        // the secret key is the seed followed by the public key, written right into the wiped buffer
+        let mut signing_key = Secret::new([0u8; ED25519_SECRET_KEY_BYTE_LENGTH]);
+        crypto_sign_seed_keypair_inplace(&mut public_key, signing_key.expose_mut(), seed);
         Self {
-            persistent_public_key: PublicKey::PublicKeyTypeEd25519(*public_key.as_array()),
+            persistent_public_key: PublicKey::PublicKeyTypeEd25519(public_key),
             signing_key
         }
     }
@@ -58,8 +66,26 @@ impl From<&[u8; SEED_LENGTH]> for Keychain {
 
 #[derive(Debug, thiserror::Error)]
 pub enum KeychainError {
-    #[error("Wrong seed: expected {expected}, found {actual}")]
-    WrongLength {expected: u32, actual: u32},
+    #[error("Not a valid S... seed")]
+    InvalidSeed(#[from] StrKeyError),
+    #[error("Cannot read the seed file {}", path.display())]
+    SeedFile {path: PathBuf, source: std::io::Error},
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debug_redacts_the_signing_key() {
+        let seed: [u8; SEED_LENGTH] = std::array::from_fn(|index| index as u8 + 1);
+        let keychain = Keychain::from(&seed);
+        let debug = format!("{:?}", keychain);
+        assert!(debug.contains("Secret([REDACTED])"), "{}", debug);
+        assert!(!debug.contains("1, 2, 3, 4, 5, 6, 7, 8"), "{}", debug);
+        let public_key = format!("{:?}", keychain.persistent_public_key());
+        assert!(debug.contains(public_key.trim_matches(['[', ']'])), "{}", debug);
+    }
+}
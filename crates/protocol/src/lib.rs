@@ -1,11 +1,17 @@
 pub mod connection_authentication;
 pub mod keychain;
+pub mod secret;
+pub mod signer;
 pub mod remote_node_info;
+pub mod scp;
 pub mod stellar_protocol;
 pub mod errors;
+pub mod flow_control;
 pub mod protocol;
+pub mod session;
 
 pub mod node_config;
+pub mod profiles;
 pub(crate) mod constants;
 mod prelude;
 
@@ -1,40 +1,116 @@
 use std::fmt::Debug;
-use std::net::SocketAddr;
-use std::str::FromStr;
+use std::path::PathBuf;
 use xdr::compound_types::LimitedString;
 use serde::de::{Error, Deserialize, Deserializer};
-use crate::errors::StellarError;
+use utils::strkey::{decode_public_key, decode_seed};
+use xdr::types::NodeId;
+use crate::errors::{StellarError, VerificationError};
+use crate::secret::Secret;
 
-#[allow(dead_code)]
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct NodeConfig {
     pub node_info: NodeInfo,
+    /// Host name or IP address of the node to connect to.
     pub ip: String,
     pub listening_port: i32,
+    /// `host:port` of further nodes, e.g. the ones `probe` checks by default.
+    #[serde(default)]
+    pub known_peers: Vec<String>,
+    /// If set, only these peers (`G...` node ids) are accepted.
+    #[serde(default, deserialize_with = "optional_node_ids")]
+    pub allowed_peers: Option<Vec<NodeId>>,
+    /// Peers that are never accepted.
+    #[serde(default, deserialize_with = "node_ids")]
+    pub denied_peers: Vec<NodeId>,
+    #[serde(default)]
+    pub timeouts: Timeouts,
+    /// Our `S...` seed. Without it or `seed_file` the node id changes on every run.
+    #[serde(default)]
+    pub seed: Option<Secret<String>>,
+    /// File holding our seed, used if `seed` is not set.
+    #[serde(default)]
+    pub seed_file: Option<PathBuf>,
+    /// Unix socket of a `signer`, which holds the seed instead of us.
+    #[serde(default)]
+    pub signer_socket: Option<PathBuf>,
+}
+
+/// Timeouts in milliseconds, see [`TimeoutPhase`](crate::errors::TimeoutPhase).
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default)]
+pub struct Timeouts {
+    /// Opening the TCP connection.
+    pub connect_ms: u64,
+    /// From the connection to the completed handshake.
+    pub handshake_ms: u64,
+    /// Without any message from the peer after the handshake.
+    pub idle_ms: u64,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self { connect_ms: 5_000, handshake_ms: 10_000, idle_ms: 60_000 }
+    }
 }
-#[allow(dead_code)]
 impl NodeConfig {
-    /// Using the public mainnet node taken from here:
-    /// https://stellarbeat.io/nodes/GAAV2GCVFLNN522ORUYFV33E76VPC22E72S75AQ6MBR5V45Z5DWVPWEU?center=1
-    pub fn mainnet() -> Self {
-        let node = NodeConfig {
-            node_info: NodeInfo::mainnet(),
-            ip: "35.233.35.143".into(),
-            listening_port: 11625,
-        };
-        println!("Connecting to MAINNET node {:?}", node.sock_addr());
-        node
+    /// `host:port` of the configured node.
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.ip, self.listening_port)
     }
 
-    pub fn local() -> Self {
-        NodeConfig {
-            node_info: NodeInfo::local(),
-            ip: "127.0.0.1".into(),
-            listening_port: 11625,
+    /// Checks what deserialization cannot, naming the offending field.
+    pub fn validate(&self) -> Result<(), StellarError> {
+        let invalid = |field: &str, message: String| Err(StellarError::InvalidConfig { field: field.into(), message });
+        if self.node_info.network_id.is_empty() {
+            return invalid("node_info.network_id", "the network passphrase must not be empty".into());
+        }
+        if self.node_info.overlay_min_version > self.node_info.overlay_version {
+            return invalid("node_info.overlay_min_version", format!(
+                "{} is above overlay_version {}", self.node_info.overlay_min_version, self.node_info.overlay_version
+            ));
+        }
+        if self.ip.is_empty() {
+            return invalid("ip", "the host must not be empty".into());
         }
+        if !(1..=u16::MAX as i32).contains(&self.listening_port) {
+            return invalid("listening_port", format!("{} is not a TCP port", self.listening_port));
+        }
+        if self.signer_socket.is_some() && (self.seed.is_some() || self.seed_file.is_some()) {
+            return invalid("signer_socket", "the seed must stay with the signer, seed and seed_file must not be set".into());
+        }
+        if let Some(seed) = &self.seed {
+            if let Err(e) = decode_seed(seed.expose().trim()) {
+                return invalid("seed", e.to_string());
+            }
+        }
+        for (index, peer) in self.known_peers.iter().enumerate() {
+            let valid = peer.rsplit_once(':')
+                .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok_and(|port| port > 0));
+            if !valid {
+                return invalid(&format!("known_peers[{}]", index), format!("{} is not host:port", peer));
+            }
+        }
+        let timeouts = [
+            ("timeouts.connect_ms", self.timeouts.connect_ms),
+            ("timeouts.handshake_ms", self.timeouts.handshake_ms),
+            ("timeouts.idle_ms", self.timeouts.idle_ms),
+        ];
+        for (field, value) in timeouts {
+            if value == 0 {
+                return invalid(field, "must be greater than 0".into());
+            }
+        }
+        Ok(())
     }
-    pub fn sock_addr(&self) -> SocketAddr {
-        SocketAddr::from_str(&format!("{}:{}", self.ip, self.listening_port)).unwrap()
+
+    pub fn verify_peer(&self, peer_id: &NodeId) -> Result<(), VerificationError> {
+        if self.denied_peers.contains(peer_id) {
+            return Err(VerificationError::PeerDenied);
+        }
+        match &self.allowed_peers {
+            Some(allowed_peers) if !allowed_peers.contains(peer_id) => Err(VerificationError::PeerNotAllowed),
+            _ => Ok(()),
+        }
     }
 }
 
@@ -57,24 +133,21 @@ fn limited_string<'de, D>(deserializer: D) -> Result<LimitedString<100>, D::Erro
     LimitedString::new(s.into()) .map_err(|e| D::Error::custom(StellarError::from(e)))
 }
 
-#[allow(dead_code)]
-impl NodeInfo {
-    fn local() -> Self {
-        Self {
-            ledger_version: 19,
-            overlay_version: 29,
-            overlay_min_version: 27,
-            version_string: LimitedString::new("v19.13.0".as_bytes().to_vec()).unwrap(),
-            network_id: "Test SDF Network ; September 2015".to_string(),
-        }
-    }
-    fn mainnet() -> Self {
-        Self {
-            ledger_version: 19,
-            overlay_version: 29,
-            overlay_min_version: 27,
-            version_string: LimitedString::new("v19.13.0".as_bytes().to_vec()).unwrap(),
-            network_id: "Public Global Stellar Network ; September 2015".to_string(),
-        }
-    }
-}
\ No newline at end of file
+fn node_ids<'de, D>(deserializer: D) -> Result<Vec<NodeId>, D::Error>
+    where
+        D: Deserializer<'de>,
+{
+    let keys: Vec<String> = Deserialize::deserialize(deserializer)?;
+    keys.iter()
+        .map(|key| decode_public_key(key)
+            .map(NodeId::PublicKeyTypeEd25519)
+            .map_err(|e| D::Error::custom(format!("invalid node id {}: {}", key, e))))
+        .collect()
+}
+
+fn optional_node_ids<'de, D>(deserializer: D) -> Result<Option<Vec<NodeId>>, D::Error>
+    where
+        D: Deserializer<'de>,
+{
+    node_ids(deserializer).map(Some)
+}
@@ -0,0 +1,53 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The built-in configurations, each a complete [`NodeConfig`](crate::node_config::NodeConfig)
+/// that a user configuration file and the environment may override.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Profile {
+    #[default]
+    Pubnet,
+    Testnet,
+    Futurenet,
+    /// A stellar-core started with `--standalone` or the quickstart image on this machine.
+    Standalone,
+}
+
+impl Profile {
+    pub const ALL: [Profile; 4] = [Profile::Pubnet, Profile::Testnet, Profile::Futurenet, Profile::Standalone];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Profile::Pubnet => "pubnet",
+            Profile::Testnet => "testnet",
+            Profile::Futurenet => "futurenet",
+            Profile::Standalone => "standalone",
+        }
+    }
+
+    /// The profile as YAML, in the format of the configuration file.
+    pub fn yaml(&self) -> &'static str {
+        match self {
+            Profile::Pubnet => include_str!("../profiles/pubnet.yaml"),
+            Profile::Testnet => include_str!("../profiles/testnet.yaml"),
+            Profile::Futurenet => include_str!("../profiles/futurenet.yaml"),
+            Profile::Standalone => include_str!("../profiles/standalone.yaml"),
+        }
+    }
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Profile::ALL.into_iter()
+            .find(|profile| profile.name() == s)
+            .ok_or_else(|| format!("unknown network {}, expected one of pubnet, testnet, futurenet, standalone", s))
+    }
+}
@@ -1,10 +1,14 @@
 
 use anyhow::Result;
 
-use xdr::compound_types::XdrArchive;
+use xdr::compound_types::{read_record, record_length, XdrArchive};
 use xdr::{ReadStream, XdrCodec};
-use crate::errors::StellarError;
+use xdr::types::StellarMessage;
+use crate::connection_authentication::PeerRole;
+use crate::errors::{StellarError, VerificationError};
 
+/// Largest message stellar-core accepts.
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 0x100_0000;
 
 pub trait Protocol: Sized {
     type Message: ProtocolMessage;
@@ -12,11 +16,24 @@ pub trait Protocol: Sized {
     type NodeInfo: Sized;
     fn create_hello_message(&mut self) -> Self::Message;
     fn create_auth_message(&mut self) -> Self::Message;
+    /// Wraps a message into the next authenticated message; only possible once the handshake is completed.
+    fn create_message(&mut self, message: StellarMessage) -> Result<Self::Message, StellarError>;
     fn handle_message(&mut self, message: (&Self::Message, Vec<u8>)) -> Result<HandshakeMessageExtract>;
+    fn role(&self) -> PeerRole;
+    /// Whether the peer granted the capacity to send `message` now.
+    fn can_send(&self, message: &StellarMessage) -> bool;
+    /// Next message the protocol itself wants to send, such as SEND_MORE_EXTENDED.
+    fn take_control_message(&mut self) -> Option<Self::Message>;
+    /// What the peer told about itself, known once its Hello is verified.
+    fn remote_node_info(&self) -> Option<&Self::NodeInfo>;
 }
 
 pub trait ProtocolMessage: XdrCodec + Sized {
-    fn complete_message_size(buf: &[u8]) -> Option<usize>;
+    /// Size of the first complete message in `buf`, `None` if more bytes are needed.
+    /// Messages longer than `max_size` are rejected before they are buffered.
+    fn complete_message_size(buf: &[u8], max_size: usize) -> Result<Option<usize>, StellarError>;
+    /// The bytes of a complete message without its framing.
+    fn payload(buf: &[u8], max_size: usize) -> Result<Vec<u8>, StellarError>;
     fn decoded<T: AsRef<[u8]>>(bytes: T) -> Result<(Self, usize), StellarError> {
         let mut read_stream = ReadStream::new(bytes);
         let result = <Self as XdrCodec>::from_xdr_buffered(&mut read_stream)?;
@@ -28,16 +45,11 @@ pub trait ProtocolMessage: XdrCodec + Sized {
 }
 
 impl <T: XdrCodec> ProtocolMessage for XdrArchive<T> {
-    fn complete_message_size(buf: &[u8]) -> Option<usize> {
-        if buf.len() < 4 {
-            return None;
-        }
-        let length = 4 + (ReadStream::new(buf).read_next_u32().unwrap_or(0) as usize & 0x7f_ff_ff_ff);
-        if length <= buf.len() {
-            Some(length)
-        } else {
-            None
-        }
+    fn complete_message_size(buf: &[u8], max_size: usize) -> Result<Option<usize>, StellarError> {
+        Ok(record_length(buf, max_size)?)
+    }
+    fn payload(buf: &[u8], max_size: usize) -> Result<Vec<u8>, StellarError> {
+        Ok(read_record(&mut ReadStream::new(buf), max_size)?)
     }
 }
 
@@ -45,5 +57,12 @@ impl <T: XdrCodec> ProtocolMessage for XdrArchive<T> {
 pub enum HandshakeMessageExtract {
     Hello,
     Auth,
+    /// Any other verified message received after the handshake.
+    Message(Box<StellarMessage>),
+    /// A message that failed its own verification, such as an SCP envelope with a bad signature.
+    /// It came from the peer all right, so the connection stays open.
+    Invalid(Box<StellarMessage>, VerificationError),
+    /// Flow control message that was handled by the protocol.
+    FlowControl,
 }
 
@@ -1,3 +1,5 @@
+use serde::Serialize;
+use utils::strkey::encode_public_key;
 use xdr::types::*;
 
 
@@ -17,3 +19,35 @@ impl From<&Hello> for RemoteNodeInfo {
         }
     }
 }
+
+/// What a peer told about itself in its verified Hello.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PeerInfo {
+    /// The `G...` strkey of the peer's node id.
+    pub node_id: String,
+    pub version_str: String,
+    pub ledger_version: u32,
+    pub overlay_version: u32,
+    pub overlay_min_version: u32,
+    pub listening_port: i32,
+    /// When the peer's auth cert expires, in seconds since the Unix epoch.
+    pub cert_expiration: u64,
+    /// The highest overlay version both sides speak.
+    pub negotiated_overlay_version: u32,
+}
+
+impl PeerInfo {
+    /// `local_overlay_version` is the highest overlay version we speak.
+    pub fn new(hello: &Hello, local_overlay_version: u32) -> Self {
+        Self {
+            node_id: encode_public_key(hello.peer_id.as_binary()),
+            version_str: String::from_utf8_lossy(&hello.version_str).into_owned(),
+            ledger_version: hello.ledger_version,
+            overlay_version: hello.overlay_version,
+            overlay_min_version: hello.overlay_min_version,
+            listening_port: hello.listening_port,
+            cert_expiration: hello.cert.expiration,
+            negotiated_overlay_version: hello.overlay_version.min(local_overlay_version),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use dryoc::classic::crypto_sign::crypto_sign_verify_detached;
+use utils::strkey::encode_public_key;
+use xdr::types::{EnvelopeType, Hash, ScpEnvelope, ScpStatement, Signature};
+use xdr::XdrCodec;
+use crate::errors::VerificationError;
+use crate::signer::{Signer, SignerError};
+
+/// What the signature of an SCP envelope signs: the network id, `ENVELOPE_TYPE_SCP` and the statement,
+/// all in XDR. Unlike the auth cert, stellar-core signs these bytes themselves and not their hash.
+pub fn signature_data(network_id: &Hash, statement: &ScpStatement) -> Vec<u8> {
+    [network_id.to_xdr(), EnvelopeType::EnvelopeTypeScp.to_xdr(), statement.to_xdr()].concat()
+}
+
+/// Signs a statement as the node `signer` holds the key of, which has to be the statement's node id.
+///
+/// This needs a [`Signer`] and not an [`AsyncSigner`](crate::signer::AsyncSigner), as the signature is
+/// over all of [`signature_data`]: the Unix socket signer only signs auth certs and cannot do it.
+pub fn sign_statement(signer: &dyn Signer, network_id: &Hash, statement: ScpStatement) -> Result<ScpEnvelope, SignerError> {
+    let signature = signer.sign(&signature_data(network_id, &statement))?;
+    Ok(ScpEnvelope { statement, signature: Signature::new(signature.to_vec()).expect("an ed25519 signature has 64 bytes, as many as a Signature holds") })
+}
+
+/// Checks that the envelope was signed on our network by the node its statement is from.
+pub fn verify_envelope(network_id: &Hash, envelope: &ScpEnvelope) -> Result<(), VerificationError> {
+    let node_id = envelope.statement.node_id.as_binary();
+    let invalid = || VerificationError::ScpSignature {
+        node_id: encode_public_key(node_id),
+        slot_index: envelope.statement.slot_index,
+    };
+    let signature: &[u8; 64] = envelope.signature.get_vec().as_slice().try_into().map_err(|_| invalid())?;
+    crypto_sign_verify_detached(signature, &signature_data(network_id, &envelope.statement), node_id).map_err(|_| invalid())
+}
@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use xdr::compound_types::XdrArchive;
+use xdr::types::{AuthenticatedMessage, StellarMessage};
+
+use crate::connection_authentication::PeerRole;
+use crate::errors::{StellarError, VerificationError};
+use crate::protocol::{HandshakeMessageExtract, Protocol, ProtocolMessage, DEFAULT_MAX_FRAME_SIZE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionState {
+    AwaitingHello,
+    AwaitingAuth,
+    Authenticated,
+    Closed,
+}
+
+#[derive(Debug)]
+pub enum SessionEvent {
+    /// The handshake is completed, messages can be sent from now on.
+    Authenticated,
+    /// A verified message from the peer.
+    Message(Box<StellarMessage>),
+    /// A message from the peer that failed verification and was dropped, the session stays open.
+    InvalidMessage(Box<StellarMessage>, VerificationError),
+}
+
+/// The handshake and the message exchange with one peer, without any IO.
+///
+/// Bytes read from the socket go into [`Session::receive`], or already decoded frames into
+/// [`Session::receive_frame`]; the frames to write come out of [`Session::poll_transmit`]
+/// and whatever happened comes out of [`Session::poll_event`].
+/// The initiator's Hello is queued on creation. Any error closes the session.
+pub struct Session<P: Protocol> {
+    protocol: P,
+    state: SessionState,
+    read_buffer: Vec<u8>,
+    /// Longest frame buffered from the peer.
+    max_frame_size: usize,
+    transmits: VecDeque<P::Message>,
+    events: VecDeque<SessionEvent>,
+}
+
+impl<P: Protocol<Message = XdrArchive<AuthenticatedMessage>>> Session<P> {
+    pub fn new(mut protocol: P) -> Self {
+        let mut transmits = VecDeque::new();
+        if protocol.role() == PeerRole::WeCalledRemote {
+            transmits.push_back(protocol.create_hello_message());
+        }
+        Self {
+            protocol,
+            state: SessionState::AwaitingHello,
+            read_buffer: Vec::new(),
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            transmits,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Frames announcing more than `max_frame_size` bytes fail [`Session::receive`].
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    pub fn state(&self) -> SessionState {
+        self.state
+    }
+
+    pub fn protocol(&self) -> &P {
+        &self.protocol
+    }
+
+    /// What the peer told about itself, known once its Hello is verified.
+    pub fn peer_info(&self) -> Option<&P::NodeInfo> {
+        self.protocol.remote_node_info()
+    }
+
+    /// Feeds bytes read from the peer, handling every complete frame.
+    pub fn receive(&mut self, bytes: &[u8]) -> Result<()> {
+        if self.state == SessionState::Closed {
+            return Err(StellarError::SessionClosed.into());
+        }
+        self.read_buffer.extend_from_slice(bytes);
+        let result = self.handle_frames();
+        self.close_on_error(result)
+    }
+
+    /// Feeds a frame decoded elsewhere, along with its payload the MAC is computed over.
+    pub fn receive_frame(&mut self, message: P::Message, payload: Vec<u8>) -> Result<()> {
+        if self.state == SessionState::Closed {
+            return Err(StellarError::SessionClosed.into());
+        }
+        let result = self.handle_message(message, payload);
+        self.close_on_error(result)
+    }
+
+    fn close_on_error(&mut self, result: Result<()>) -> Result<()> {
+        if result.is_err() {
+            self.state = SessionState::Closed;
+        }
+        result
+    }
+
+    /// The peer closed the connection.
+    pub fn receive_end(&mut self) -> Result<(), StellarError> {
+        self.state = SessionState::Closed;
+        if self.read_buffer.is_empty() {
+            Ok(())
+        } else {
+            Err(StellarError::ConnectionResetByPeer)
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.state = SessionState::Closed;
+    }
+
+    /// Queues a message for the peer once the handshake is completed.
+    pub fn send(&mut self, message: StellarMessage) -> Result<(), StellarError> {
+        match self.state {
+            SessionState::Authenticated => {}
+            SessionState::Closed => return Err(StellarError::SessionClosed),
+            _ => return Err(StellarError::NotAuthenticated),
+        }
+        let message = self.protocol.create_message(message)?;
+        self.transmits.push_back(message);
+        Ok(())
+    }
+
+    /// Whether the peer granted the capacity to send `message` now.
+    pub fn can_send(&self, message: &StellarMessage) -> bool {
+        self.protocol.can_send(message)
+    }
+
+    /// Next frame to write to the peer.
+    pub fn poll_transmit(&mut self) -> Option<P::Message> {
+        self.transmits.pop_front()
+    }
+
+    pub fn poll_event(&mut self) -> Option<SessionEvent> {
+        self.events.pop_front()
+    }
+
+    fn handle_frames(&mut self) -> Result<()> {
+        while let Some(size) = P::Message::complete_message_size(&self.read_buffer, self.max_frame_size)? {
+            let frame: Vec<u8> = self.read_buffer.drain(..size).collect();
+            let (message, _) = P::Message::decoded(&frame)?;
+            let payload = P::Message::payload(&frame, self.max_frame_size)?;
+            self.handle_message(message, payload)?;
+        }
+        Ok(())
+    }
+
+    fn handle_message(&mut self, message: P::Message, payload: Vec<u8>) -> Result<()> {
+        let Some(AuthenticatedMessage::V0(authenticated)) = message.get_vec().first() else {
+            return Err(StellarError::ExpectedMoreMessages.into());
+        };
+        match (&authenticated.message, self.state) {
+            (StellarMessage::Hello(_), SessionState::AwaitingHello)
+            | (StellarMessage::Auth(_), SessionState::AwaitingAuth)
+            | (StellarMessage::ErrorMsg(_), _) => {}
+            (StellarMessage::Hello(_), state) => return Err(StellarError::IllegalTransition { state, message: "Hello" }.into()),
+            (StellarMessage::Auth(_), state) => return Err(StellarError::IllegalTransition { state, message: "Auth" }.into()),
+            (_, SessionState::Authenticated) => {}
+            _ => return Err(StellarError::NotAuthenticated.into()),
+        }
+
+        match self.protocol.handle_message((&message, payload))? {
+            HandshakeMessageExtract::Hello => {
+                let reply = match self.protocol.role() {
+                    PeerRole::WeCalledRemote => self.protocol.create_auth_message(),
+                    PeerRole::RemoteCalledUs => self.protocol.create_hello_message(),
+                };
+                self.transmits.push_back(reply);
+                self.state = SessionState::AwaitingAuth;
+            }
+            HandshakeMessageExtract::Auth => {
+                if self.protocol.role() == PeerRole::RemoteCalledUs {
+                    let auth = self.protocol.create_auth_message();
+                    self.transmits.push_back(auth);
+                }
+                self.state = SessionState::Authenticated;
+                self.events.push_back(SessionEvent::Authenticated);
+            }
+            HandshakeMessageExtract::Message(message) => self.events.push_back(SessionEvent::Message(message)),
+            HandshakeMessageExtract::Invalid(message, error) => self.events.push_back(SessionEvent::InvalidMessage(message, error)),
+            HandshakeMessageExtract::FlowControl => {}
+        }
+        while let Some(message) = self.protocol.take_control_message() {
+            self.transmits.push_back(message);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use std::future::Future;
+use std::pin::Pin;
+use xdr::types::{Curve25519Public, Uint256};
+use crate::connection_authentication::auth_cert_signature_data;
+use crate::keychain::{Keychain, Uint512};
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Signs with the node's ed25519 key, which does not have to be in our memory.
+///
+/// We sign the auth cert of each connection, see
+/// [`ConnectionAuthentication`](crate::connection_authentication::ConnectionAuthentication), and SCP
+/// statements, see [`sign_statement`](crate::scp::sign_statement). Those are signed as they are, not
+/// their hash, which is why an [`AsyncSigner`] cannot sign them.
+pub trait Signer: Send + Sync {
+    /// The public key of the node, its node id.
+    fn public_key(&self) -> Uint256;
+    fn sign(&self, message: &[u8]) -> Result<Uint512, SignerError>;
+}
+
+/// A signer that has to wait for its key, e.g. in another process. Every [`Signer`] is one too.
+///
+/// It only signs auth certs. It is given what the cert consists of rather than the hash to sign,
+/// so that a signer outside our process can refuse to sign anything else.
+pub trait AsyncSigner: Send + Sync {
+    fn public_key(&self) -> Uint256;
+    /// Signs [`auth_cert_signature_data`] of a cert for `network_id`, the hash of the network passphrase.
+    fn sign_auth_cert<'a>(
+        &'a self,
+        network_id: &'a Uint256,
+        expiration: u64,
+        per_connection_pubkey: &'a Curve25519Public,
+    ) -> BoxFuture<'a, Result<Uint512, SignerError>>;
+}
+
+impl<S: Signer> AsyncSigner for S {
+    fn public_key(&self) -> Uint256 {
+        Signer::public_key(self)
+    }
+
+    fn sign_auth_cert<'a>(
+        &'a self,
+        network_id: &'a Uint256,
+        expiration: u64,
+        per_connection_pubkey: &'a Curve25519Public,
+    ) -> BoxFuture<'a, Result<Uint512, SignerError>> {
+        Box::pin(async move { Signer::sign(self, &auth_cert_signature_data(network_id, expiration, per_connection_pubkey)) })
+    }
+}
+
+/// The seed is in our memory.
+impl Signer for Keychain {
+    fn public_key(&self) -> Uint256 {
+        *self.persistent_public_key()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Uint512, SignerError> {
+        Ok(Keychain::sign(self, message))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SignerError {
+    #[error("The signer cannot be reached")]
+    Unavailable(#[source] std::io::Error),
+    #[error("The signer refused: {0}")]
+    Refused(String),
+    #[error("The signer sent an invalid response")]
+    InvalidResponse,
+}
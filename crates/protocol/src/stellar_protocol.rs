@@ -1,10 +1,12 @@
 
-use crate::connection_authentication::ConnectionAuthentication;
+use crate::connection_authentication::{ConnectionAuthentication, PeerRole};
+use crate::flow_control::FlowControl;
 use crate::node_config::NodeConfig;
 use crate::errors::{StellarError, VerificationError};
-use crate::remote_node_info::RemoteNodeInfo;
-use crate::errors::VerificationError::{MacKey, SequenceMismatch};
+use crate::remote_node_info::{PeerInfo, RemoteNodeInfo};
+use crate::errors::VerificationError::{ConnectedToSelf, MacKey, NetworkIdMismatch, NonceReused, OverlayVersionMismatch, SequenceMismatch};
 use crate::protocol::Protocol;
+use crate::scp::verify_envelope;
 
 use crate::protocol::HandshakeMessageExtract;
 use utils::sha2::{create_sha256_hmac, verify_sha256_hmac};
@@ -14,53 +16,89 @@ use anyhow::Result;
 use xdr::compound_types::XdrArchive;
 use xdr::XdrCodec;
 use crate::constants::SHA256_LENGTH;
+use crate::secret::Secret;
 
 pub struct StellarProtocol {
     node_config: NodeConfig,
     authentication: ConnectionAuthentication,
+    role: PeerRole,
     local_nonce: Uint256,
     /// We don't need to store them for handshake process, but if we want to send more and receive more messages, we need to store them
     local_sequence: u64,
     remote_sequence: u64,
-    sending_mac_key: Option<Vec<u8>>,
-    receiving_mac_key: Option<Vec<u8>>,
-    time_provider: Box<dyn Fn() -> u64>
+    /// Derived once the remote Hello is verified.
+    mac_keys: Option<MacKeys>,
+    /// Known once the remote Hello is verified.
+    peer_info: Option<PeerInfo>,
+    authenticated: bool,
+    flow_control: FlowControl,
+    time_provider: Box<dyn Fn() -> u64 + Send>
+}
+
+struct MacKeys {
+    sending: Secret<Vec<u8>>,
+    receiving: Secret<Vec<u8>>,
 }
 
 impl StellarProtocol {
-    pub fn new(node_config: NodeConfig, local_nonce: Uint256, authentication: ConnectionAuthentication, time_provider: Box<dyn Fn() -> u64>) -> Self {
+    pub fn new(node_config: NodeConfig, local_nonce: Uint256, authentication: ConnectionAuthentication, role: PeerRole, time_provider: Box<dyn Fn() -> u64 + Send>) -> Self {
         Self {
             node_config,
             authentication,
+            role,
             local_nonce,
-            sending_mac_key: None,
+            mac_keys: None,
+            peer_info: None,
             local_sequence: 0,
             remote_sequence: 0,
             time_provider: Box::new(time_provider),
-            receiving_mac_key: None,
+            authenticated: false,
+            flow_control: FlowControl::new(),
         }
     }
     fn mac_for_authenticated_message(&self, message: &StellarMessage) -> HmacSha256Mac {
-        if let Some(sending_mac_key) = &self.sending_mac_key {
+        if let Some(MacKeys { sending: sending_mac_key, .. }) = &self.mac_keys {
             let data = [&self.local_sequence.to_be_bytes(), message.to_xdr().as_slice()].concat();
             let mut mac = [0u8; SHA256_LENGTH];
-            let sha_result = create_sha256_hmac(&data, sending_mac_key);
+            let sha_result = create_sha256_hmac(&data, sending_mac_key.expose());
             mac.copy_from_slice(&sha_result);
             HmacSha256Mac { mac }
         } else {
             HmacSha256Mac { mac: [0u8; SHA256_LENGTH] }
         }
     }
+    fn authenticated_message(&mut self, message: StellarMessage) -> XdrArchive<AuthenticatedMessage> {
+        let mac = self.mac_for_authenticated_message(&message);
+        let message = XdrArchive::new(vec![AuthenticatedMessage::V0(AuthenticatedMessageV0{message, sequence: self.local_sequence, mac})]);
+        self.inc_loc_seq();
+        message
+    }
     fn inc_loc_seq(&mut self) {
         self.local_sequence += 1;
     }
     fn inc_rem_seq(&mut self) {
         self.remote_sequence += 1;
     }
+    fn verify_hello(&self, hello: &Hello) -> Result<(), VerificationError> {
+        let node_info = &self.node_config.node_info;
+        if hello.network_id != self.authentication.network_id() {
+            return Err(NetworkIdMismatch);
+        }
+        if hello.overlay_min_version > node_info.overlay_version || hello.overlay_version < node_info.overlay_min_version {
+            return Err(OverlayVersionMismatch { remote_min: hello.overlay_min_version, remote_max: hello.overlay_version });
+        }
+        if hello.peer_id.as_binary() == self.authentication.public_key() {
+            return Err(ConnectedToSelf);
+        }
+        if hello.nonce == self.local_nonce {
+            return Err(NonceReused);
+        }
+        self.node_config.verify_peer(&hello.peer_id)
+    }
     fn verify_v0_message(&self, message: &AuthenticatedMessageV0, body: &[u8]) -> Result<(), VerificationError> {
         if message.sequence != self.remote_sequence {
             Err(SequenceMismatch)
-        } else if self.receiving_mac_key.as_ref().map_or(false, |key| verify_sha256_hmac(&message.mac.mac, key, body)) {
+        } else if self.mac_keys.as_ref().is_some_and(|keys| verify_sha256_hmac(&message.mac.mac, keys.receiving.expose(), body)) {
             Ok(())
         } else {
             Err(MacKey)
@@ -71,7 +109,7 @@ impl StellarProtocol {
 impl Protocol for StellarProtocol {
     type Message = XdrArchive<AuthenticatedMessage>;
     type MessageExtract = Result<HandshakeMessageExtract, StellarError>;
-    type NodeInfo = RemoteNodeInfo;
+    type NodeInfo = PeerInfo;
     fn create_hello_message(&mut self) -> XdrArchive<AuthenticatedMessage> {
         let hello = Hello {
             ledger_version: self.node_config.node_info.ledger_version,
@@ -80,48 +118,99 @@ impl Protocol for StellarProtocol {
             network_id: self.authentication.network_id(),
             version_str: self.node_config.node_info.version_string.clone(),
             listening_port: self.node_config.listening_port,
-            peer_id: NodeId::PublicKeyTypeEd25519(*self.authentication.keychain().persistent_public_key()),
-            cert: self.authentication.auth_cert((self.time_provider)()).clone(),
+            peer_id: NodeId::PublicKeyTypeEd25519(*self.authentication.public_key()),
+            cert: self.authentication.auth_cert().clone(),
             nonce: self.local_nonce,
         };
         let message = AuthenticatedMessage::V0(AuthenticatedMessageV0{message: StellarMessage::Hello(hello), mac: HmacSha256Mac{mac: [0; 32]}, sequence: self.local_sequence});
         XdrArchive::new(vec![message])
     }
     fn create_auth_message(&mut self) -> XdrArchive<AuthenticatedMessage> {
-        let message = StellarMessage::Auth(Auth{flags: 100});
-        let mac = self.mac_for_authenticated_message(&message);
-        let message = XdrArchive::new(vec![AuthenticatedMessage::V0(AuthenticatedMessageV0{message, sequence: self.local_sequence, mac})]);
-        self.inc_loc_seq();
-        message
+        self.authenticated_message(StellarMessage::Auth(Auth{flags: FlowControl::auth_flags()}))
+    }
+    fn create_message(&mut self, message: StellarMessage) -> Result<XdrArchive<AuthenticatedMessage>, StellarError> {
+        if !self.authenticated {
+            return Err(StellarError::NotAuthenticated);
+        }
+        self.flow_control.reserve(&message)?;
+        Ok(self.authenticated_message(message))
     }
     fn handle_message(&mut self, result: (&XdrArchive<AuthenticatedMessage>, Vec<u8>)) -> Result<HandshakeMessageExtract> {
-        let binding = result.0.get_vec().first();
-        let AuthenticatedMessage::V0(message) = &binding.unwrap();
+        let Some(AuthenticatedMessage::V0(message)) = result.0.get_vec().first() else {
+            return Err(StellarError::ExpectedMoreMessages.into());
+        };
         if let StellarMessage::Hello(hello) = &message.message {
-            self.authentication.verify_cert((self.time_provider)(), hello.peer_id.as_binary(), &hello.cert)?;
+            if self.authenticated {
+                return Err(StellarError::UnexpectedMessage.into());
+            }
+            self.verify_hello(hello).map_err(StellarError::from)?;
+            self.authentication.verify_cert((self.time_provider)(), hello.peer_id.as_binary(), &hello.cert).map_err(StellarError::from)?;
             self.local_sequence = 0;
             self.remote_sequence = 0;
+            self.authenticated = false;
+            self.flow_control = FlowControl::new();
+            self.peer_info = Some(PeerInfo::new(hello, self.node_config.node_info.overlay_version));
             let remote_node_info = RemoteNodeInfo::from(hello);
-            self.sending_mac_key = Some(self.authentication.mac_key(
-                &self.local_nonce,
-                &remote_node_info.nonce,
-                &remote_node_info.public_key.key,
-                true,
-            ));
-            self.receiving_mac_key = Some(self.authentication.mac_key(
-                &self.local_nonce,
-                &remote_node_info.nonce,
-                &remote_node_info.public_key.key,
-                false,
-            ));
+            self.mac_keys = Some(MacKeys {
+                sending: self.authentication.sending_mac_key(
+                    &self.local_nonce,
+                    &remote_node_info.nonce,
+                    &remote_node_info.public_key.key,
+                    self.role,
+                ),
+                receiving: self.authentication.receiving_mac_key(
+                    &self.local_nonce,
+                    &remote_node_info.nonce,
+                    &remote_node_info.public_key.key,
+                    self.role,
+                ),
+            });
             Ok(HandshakeMessageExtract::Hello)
+        } else if let StellarMessage::ErrorMsg(error) = &message.message {
+            // stellar-core sends errors without MAC and sequence, possibly before it knows our keys
+            Err(StellarError::PeerError {
+                code: error.code,
+                message: String::from_utf8_lossy(&error.msg).into_owned(),
+            }.into())
         } else {
-            self.verify_v0_message(message, &result.1[4..&result.1.len() - 32])?;
+            self.verify_v0_message(message, &result.1[4..&result.1.len() - 32]).map_err(StellarError::from)?;
             self.inc_rem_seq();
             match &message.message {
-                StellarMessage::Auth(_) => {Ok(HandshakeMessageExtract::Auth)},
-                _ => {unreachable!()}
+                StellarMessage::Auth(_) if self.authenticated => Err(StellarError::UnexpectedMessage.into()),
+                StellarMessage::Auth(_) => {
+                    self.authenticated = true;
+                    self.flow_control.start();
+                    Ok(HandshakeMessageExtract::Auth)
+                },
+                _ if !self.authenticated => Err(StellarError::NotAuthenticated.into()),
+                message => {
+                    self.flow_control.received(message)?;
+                    match message {
+                        StellarMessage::SendMoreExtended(_) => Ok(HandshakeMessageExtract::FlowControl),
+                        // stellar-core drops such an envelope but keeps the peer, which may only have relayed it
+                        StellarMessage::ScpMessage(envelope) => match verify_envelope(&self.authentication.network_id(), envelope) {
+                            Ok(()) => Ok(HandshakeMessageExtract::Message(Box::new(message.clone()))),
+                            Err(error) => Ok(HandshakeMessageExtract::Invalid(Box::new(message.clone()), error)),
+                        },
+                        message => Ok(HandshakeMessageExtract::Message(Box::new(message.clone()))),
+                    }
+                },
             }
         }
     }
+    fn role(&self) -> PeerRole {
+        self.role
+    }
+    fn can_send(&self, message: &StellarMessage) -> bool {
+        self.flow_control.can_send(message)
+    }
+    fn take_control_message(&mut self) -> Option<XdrArchive<AuthenticatedMessage>> {
+        if !self.authenticated {
+            return None;
+        }
+        self.flow_control.take_send_more().map(|message| self.authenticated_message(message))
+    }
+    fn remote_node_info(&self) -> Option<&PeerInfo> {
+        self.peer_info.as_ref()
+    }
 }
@@ -0,0 +1,105 @@
+//! Helpers for testing the handshake without a network: a scriptable [`MockPeer`](mock_peer::MockPeer)
+//! and clients configured for it.
+pub mod mock_peer;
+
+use std::net::SocketAddr;
+use anyhow::Result;
+use dryoc::rng::copy_randombytes;
+use handshake::connection::Connection;
+use handshake::handshake::execute_handshake;
+use protocol::connection_authentication::{ConnectionAuthentication, PeerRole};
+use protocol::errors::StellarError;
+use protocol::keychain::Keychain;
+use protocol::node_config::{NodeConfig, NodeInfo, Timeouts};
+use protocol::scp::sign_statement;
+use protocol::secret::Secret;
+use protocol::stellar_protocol::StellarProtocol;
+use utils::misc::{generate_encoded_seed, generate_nonce, get_current_u64_milliseconds};
+use utils::sha2::create_sha256;
+use xdr::compound_types::LimitedString;
+use xdr::types::{NodeId, ScpBallot, ScpEnvelope, ScpStatement, ScpStatementExternalize, ScpStatementPledges, Signature, StellarMessage};
+
+pub const NETWORK: &str = "Standalone Network ; February 2017";
+pub const LEDGER_VERSION: u32 = 22;
+pub const OVERLAY_VERSION: u32 = 35;
+pub const OVERLAY_MIN_VERSION: u32 = 33;
+
+/// A client configuration for the standalone network with timeouts short enough for tests;
+/// the handshake times out before the idle check could fail it.
+pub fn node_config() -> NodeConfig {
+    NodeConfig {
+        node_info: NodeInfo {
+            ledger_version: LEDGER_VERSION,
+            overlay_version: OVERLAY_VERSION,
+            overlay_min_version: OVERLAY_MIN_VERSION,
+            version_string: LimitedString::new(b"test-client".to_vec()).unwrap(),
+            network_id: NETWORK.into(),
+        },
+        ip: "127.0.0.1".into(),
+        listening_port: 11625,
+        known_peers: vec![],
+        allowed_peers: None,
+        denied_peers: vec![],
+        timeouts: Timeouts { connect_ms: 1_000, handshake_ms: 1_000, idle_ms: 2_000 },
+        seed: None,
+        seed_file: None,
+        signer_socket: None,
+    }
+}
+
+pub fn keychain() -> Keychain {
+    Keychain::try_from(generate_encoded_seed().as_str()).unwrap()
+}
+
+pub fn protocol(node_config: &NodeConfig, keychain: &Keychain, role: PeerRole) -> StellarProtocol {
+    let mut per_connection_secret_key = Secret::new([0u8; 32]);
+    copy_randombytes(per_connection_secret_key.expose_mut());
+    let now = get_current_u64_milliseconds();
+    let authentication = ConnectionAuthentication::new(keychain, &node_config.node_info.network_id, per_connection_secret_key, now).unwrap();
+    StellarProtocol::new(node_config.clone(), generate_nonce(), authentication, role, Box::new(get_current_u64_milliseconds))
+}
+
+/// Opens a connection as the initiator without running the handshake.
+pub async fn connect(address: SocketAddr, node_config: &NodeConfig, keychain: &Keychain) -> Result<Connection<StellarProtocol>, StellarError> {
+    let protocol = protocol(node_config, keychain, PeerRole::WeCalledRemote);
+    Connection::connect(protocol, address, node_config.timeouts.clone()).await
+}
+
+/// Connects and runs the handshake.
+pub async fn handshake(address: SocketAddr, node_config: &NodeConfig) -> Result<Connection<StellarProtocol>> {
+    let mut connection = connect(address, node_config, &keychain()).await?;
+    execute_handshake(&mut connection).await?;
+    Ok(connection)
+}
+
+/// An SCP EXTERNALIZE of `value` for slot 1.
+pub fn scp_statement(node_id: NodeId, value: Vec<u8>) -> ScpStatement {
+    ScpStatement {
+        node_id,
+        slot_index: 1,
+        pledges: ScpStatementPledges::ScpStExternalize(ScpStatementExternalize {
+            commit: ScpBallot { counter: 1, value: LimitedString::new(value).unwrap() },
+            n_h: 1,
+            commit_quorum_set_hash: [0; 32],
+        }),
+    }
+}
+
+/// An SCP message with a zero signature; flooded, so flow control applies to it.
+pub fn scp_message(node_id: NodeId, value: Vec<u8>) -> StellarMessage {
+    StellarMessage::ScpMessage(ScpEnvelope {
+        statement: scp_statement(node_id, value),
+        signature: Signature::new(vec![0; 64]).unwrap(),
+    })
+}
+
+/// An SCP message from the node of `keychain`, signed for `network`.
+pub fn signed_scp_message(keychain: &Keychain, network: &str, value: Vec<u8>) -> StellarMessage {
+    let statement = scp_statement(NodeId::PublicKeyTypeEd25519(*keychain.persistent_public_key()), value);
+    StellarMessage::ScpMessage(sign_statement(keychain, &create_sha256(network.as_bytes()), statement).unwrap())
+}
+
+/// The `StellarError` an operation failed with; panics if it failed otherwise.
+pub fn stellar_error(error: &anyhow::Error) -> &StellarError {
+    error.downcast_ref::<StellarError>().unwrap_or_else(|| panic!("not a StellarError: {:#}", error))
+}
@@ -0,0 +1,351 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use dryoc::rng::copy_randombytes;
+use futures::{SinkExt, StreamExt};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio_util::codec::Framed;
+use handshake::codec::AuthenticatedMessageCodec;
+use protocol::connection_authentication::{ConnectionAuthentication, PeerRole};
+use protocol::keychain::Keychain;
+use protocol::secret::Secret;
+use utils::misc::{generate_encoded_seed, generate_nonce, get_current_u64_milliseconds};
+use utils::sha2::create_sha256_hmac;
+use xdr::compound_types::{LimitedString, XdrArchive};
+use xdr::types::{Auth, AuthenticatedMessage, AuthenticatedMessageV0, Error, ErrorCode, Hello, HmacSha256Mac, NodeId, Signature, StellarMessage};
+use xdr::XdrCodec;
+use crate::{LEDGER_VERSION, NETWORK, OVERLAY_MIN_VERSION, OVERLAY_VERSION};
+
+/// How the mock peer breaks the handshake.
+#[derive(Debug, Clone)]
+pub enum Misbehaviour {
+    /// Closes the connection after reading the client's Hello.
+    HangUp,
+    /// Reads, but never answers.
+    Silent,
+    /// Answers the Hello with ERROR_MSG.
+    ErrorMsg { code: ErrorCode, message: String },
+    /// Sends the first bytes of a frame, then closes the connection.
+    TruncatedFrame,
+    /// Sends a complete frame that does not decode.
+    Garbage,
+    /// Sends GET_PEERS instead of Hello.
+    MessageBeforeHello,
+    /// Sends Hello for another network.
+    WrongNetworkId,
+    /// Sends a cert that expired an hour ago.
+    ExpiredCert,
+    /// Sends a cert signed by another key than its node id.
+    BadCertSignature,
+    /// Sends a cert whose signature is cut to 32 bytes.
+    ShortCertSignature,
+    /// Sends the nonce of the client's Hello back.
+    ReflectNonce,
+    /// Sends Hello twice.
+    HelloTwice,
+    /// Sends Auth with a corrupted MAC.
+    BadMac,
+    /// Sends Auth with sequence 1.
+    WrongSequence,
+}
+
+/// What the mock peer does after the handshake.
+#[derive(Debug, Clone)]
+pub enum Action {
+    /// Sends an authenticated message; MAC and sequence are right unless the handshake misbehaved.
+    Send(StellarMessage),
+    /// Sends an authenticated message, then another copy of it with the same sequence.
+    Replay(StellarMessage),
+    /// Waits for a message from the client.
+    Receive,
+    Wait(Duration),
+    /// Closes the connection.
+    Close,
+}
+
+/// The behaviour of a [`MockPeer`]: a well-behaved stellar-core responder unless told otherwise.
+#[derive(Clone)]
+pub struct Script {
+    keychain: Keychain,
+    network_id: String,
+    overlay_min_version: u32,
+    overlay_version: u32,
+    misbehaviour: Option<Misbehaviour>,
+    actions: Vec<Action>,
+}
+
+impl Script {
+    pub fn new() -> Self {
+        Self {
+            keychain: Keychain::try_from(generate_encoded_seed().as_str()).unwrap(),
+            network_id: NETWORK.into(),
+            overlay_min_version: OVERLAY_MIN_VERSION,
+            overlay_version: OVERLAY_VERSION,
+            misbehaviour: None,
+            actions: vec![],
+        }
+    }
+
+    pub fn keychain(mut self, keychain: Keychain) -> Self {
+        self.keychain = keychain;
+        self
+    }
+
+    pub fn overlay_versions(mut self, min: u32, max: u32) -> Self {
+        self.overlay_min_version = min;
+        self.overlay_version = max;
+        self
+    }
+
+    pub fn misbehave(mut self, misbehaviour: Misbehaviour) -> Self {
+        self.misbehaviour = Some(misbehaviour);
+        self
+    }
+
+    pub fn then(mut self, action: Action) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    fn misbehaves(&self, misbehaviour: fn(&Misbehaviour) -> bool) -> bool {
+        self.misbehaviour.as_ref().is_some_and(misbehaviour)
+    }
+}
+
+impl Default for Script {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A stellar-core stand-in listening on localhost for a single connection.
+///
+/// It answers the client's Hello and Auth as a responder does and then runs the actions of its [`Script`].
+pub struct MockPeer {
+    address: SocketAddr,
+    node_id: NodeId,
+    task: JoinHandle<Result<Vec<StellarMessage>>>,
+}
+
+impl MockPeer {
+    pub async fn start(script: Script) -> Result<MockPeer> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        let node_id = NodeId::PublicKeyTypeEd25519(*script.keychain.persistent_public_key());
+        let task = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await?;
+            run(socket, script).await
+        });
+        Ok(MockPeer { address, node_id, task })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub fn node_id(&self) -> &NodeId {
+        &self.node_id
+    }
+
+    /// Waits until the script is done and the client closed the connection,
+    /// returning what the client sent after the handshake.
+    pub async fn finish(self) -> Result<Vec<StellarMessage>> {
+        self.task.await?
+    }
+}
+
+struct MockConnection {
+    framed: Framed<TcpStream, AuthenticatedMessageCodec>,
+    sending_mac_key: Option<Secret<Vec<u8>>>,
+    receiving_mac_key: Option<Secret<Vec<u8>>>,
+    sequence: u64,
+    receiving_sequence: u64,
+    received: Vec<StellarMessage>,
+}
+
+impl MockConnection {
+    /// Hello and ERROR_MSG go without MAC and sequence.
+    async fn send_unauthenticated(&mut self, message: StellarMessage) -> Result<()> {
+        let message = AuthenticatedMessageV0 { sequence: 0, message, mac: HmacSha256Mac { mac: [0; 32] } };
+        self.framed.send(XdrArchive::new(vec![AuthenticatedMessage::V0(message)])).await?;
+        Ok(())
+    }
+
+    async fn send(&mut self, message: StellarMessage, corrupt_mac: bool) -> Result<()> {
+        let key = self.sending_mac_key.as_ref().ok_or_else(|| anyhow!("no MAC key before the Hello"))?;
+        let data = [&self.sequence.to_be_bytes(), message.to_xdr().as_slice()].concat();
+        let mut mac = [0u8; 32];
+        mac.copy_from_slice(&create_sha256_hmac(&data, key.expose()));
+        if corrupt_mac {
+            mac[0] ^= 0xff;
+        }
+        let message = AuthenticatedMessageV0 { sequence: self.sequence, message, mac: HmacSha256Mac { mac } };
+        self.sequence += 1;
+        self.framed.send(XdrArchive::new(vec![AuthenticatedMessage::V0(message)])).await?;
+        Ok(())
+    }
+
+    /// Next message of the client, `None` once it closed the connection.
+    ///
+    /// Once the receiving MAC key is known, every message must carry the next sequence and a valid MAC.
+    async fn receive(&mut self) -> Result<Option<StellarMessage>> {
+        let Some(frame) = self.framed.next().await else {
+            return Ok(None);
+        };
+        let (frame, _) = frame?;
+        let Some(AuthenticatedMessage::V0(message)) = frame.get_vec().first() else {
+            return Err(anyhow!("empty frame"));
+        };
+        if let Some(key) = &self.receiving_mac_key {
+            if message.sequence != self.receiving_sequence {
+                return Err(anyhow!("the client sent sequence {}, expected {}", message.sequence, self.receiving_sequence));
+            }
+            let data = [&message.sequence.to_be_bytes(), message.message.to_xdr().as_slice()].concat();
+            if create_sha256_hmac(&data, key.expose()) != message.mac.mac {
+                return Err(anyhow!("the client sent a wrong MAC with sequence {}", message.sequence));
+            }
+            self.receiving_sequence += 1;
+        }
+        Ok(Some(message.message.clone()))
+    }
+
+    async fn write_raw(&mut self, bytes: &[u8]) -> Result<()> {
+        self.framed.get_mut().write_all(bytes).await?;
+        Ok(())
+    }
+
+    /// Records what the client sends until it closes the connection.
+    async fn drain(mut self) -> Result<Vec<StellarMessage>> {
+        while let Some(message) = self.receive().await? {
+            self.received.push(message);
+        }
+        Ok(self.received)
+    }
+}
+
+async fn run(socket: TcpStream, script: Script) -> Result<Vec<StellarMessage>> {
+    let mut connection = MockConnection {
+        framed: Framed::new(socket, AuthenticatedMessageCodec::default()),
+        sending_mac_key: None,
+        receiving_mac_key: None,
+        sequence: 0,
+        receiving_sequence: 0,
+        received: vec![],
+    };
+    if script.misbehaves(|m| matches!(m, Misbehaviour::MessageBeforeHello)) {
+        connection.send_unauthenticated(StellarMessage::GetPeers).await?;
+        return connection.drain().await;
+    }
+    let Some(StellarMessage::Hello(client_hello)) = connection.receive().await? else {
+        return Err(anyhow!("the client did not start with Hello"));
+    };
+    match &script.misbehaviour {
+        Some(Misbehaviour::HangUp) => return Ok(vec![]),
+        Some(Misbehaviour::Silent) => return connection.drain().await,
+        Some(Misbehaviour::ErrorMsg { code, message }) => {
+            let error = Error { code: *code, msg: LimitedString::new(message.as_bytes().to_vec())? };
+            connection.send_unauthenticated(StellarMessage::ErrorMsg(error)).await?;
+            return connection.drain().await;
+        }
+        Some(Misbehaviour::TruncatedFrame) => {
+            // the record marking announces 64 bytes, only 8 follow
+            connection.write_raw(&[0x80, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 0]).await?;
+            return Ok(vec![]);
+        }
+        Some(Misbehaviour::Garbage) => {
+            // AuthenticatedMessage version 7 does not exist
+            connection.write_raw(&[0x80, 0, 0, 4, 0, 0, 0, 7]).await?;
+            return connection.drain().await;
+        }
+        _ => {}
+    }
+
+    let network_id = if script.misbehaves(|m| matches!(m, Misbehaviour::WrongNetworkId)) {
+        "Mock Network ; the wrong one"
+    } else {
+        script.network_id.as_str()
+    };
+    let mut now = get_current_u64_milliseconds();
+    if script.misbehaves(|m| matches!(m, Misbehaviour::ExpiredCert)) {
+        now -= 2 * 3600 * 1000;
+    }
+    let mut per_connection_secret_key = Secret::new([0u8; 32]);
+    copy_randombytes(per_connection_secret_key.expose_mut());
+    let mut authentication = if script.misbehaves(|m| matches!(m, Misbehaviour::BadCertSignature)) {
+        let impostor = Keychain::try_from(generate_encoded_seed().as_str())?;
+        ConnectionAuthentication::new(&impostor, network_id, per_connection_secret_key, now)?
+    } else {
+        ConnectionAuthentication::new(&script.keychain, network_id, per_connection_secret_key, now)?
+    };
+    let nonce = if script.misbehaves(|m| matches!(m, Misbehaviour::ReflectNonce)) {
+        client_hello.nonce
+    } else {
+        generate_nonce()
+    };
+    let mut cert = authentication.auth_cert().clone();
+    if script.misbehaves(|m| matches!(m, Misbehaviour::ShortCertSignature)) {
+        cert.sig = Signature::new(cert.sig.get_vec()[..32].to_vec())?;
+    }
+    let hello = Hello {
+        ledger_version: LEDGER_VERSION,
+        overlay_version: script.overlay_version,
+        overlay_min_version: script.overlay_min_version,
+        network_id: authentication.network_id(),
+        version_str: LimitedString::new(b"mock-peer".to_vec())?,
+        listening_port: 11625,
+        peer_id: NodeId::PublicKeyTypeEd25519(*script.keychain.persistent_public_key()),
+        cert,
+        nonce,
+    };
+    connection.send_unauthenticated(StellarMessage::Hello(hello.clone())).await?;
+    if script.misbehaves(|m| matches!(m, Misbehaviour::HelloTwice)) {
+        connection.send_unauthenticated(StellarMessage::Hello(hello)).await?;
+        return connection.drain().await;
+    }
+    connection.sending_mac_key = Some(authentication.sending_mac_key(
+        &nonce,
+        &client_hello.nonce,
+        &client_hello.cert.pubkey.key,
+        PeerRole::RemoteCalledUs,
+    ));
+    connection.receiving_mac_key = Some(authentication.receiving_mac_key(
+        &nonce,
+        &client_hello.nonce,
+        &client_hello.cert.pubkey.key,
+        PeerRole::RemoteCalledUs,
+    ));
+
+    let Some(StellarMessage::Auth(_)) = connection.receive().await? else {
+        return Err(anyhow!("the client did not send Auth"));
+    };
+    // checked only now, a client that rejected our Hello never sends Auth
+    let NodeId::PublicKeyTypeEd25519(client_key) = &client_hello.peer_id;
+    authentication
+        .verify_cert(get_current_u64_milliseconds(), client_key, &client_hello.cert)
+        .map_err(|error| anyhow!("the client sent an invalid cert: {error}"))?;
+    if script.misbehaves(|m| matches!(m, Misbehaviour::WrongSequence)) {
+        connection.sequence = 1;
+    }
+    let auth = StellarMessage::Auth(Auth { flags: xdr::constants::AUTH_MSG_FLAG_FLOW_CONTROL_BYTES_REQUESTED });
+    connection.send(auth, script.misbehaves(|m| matches!(m, Misbehaviour::BadMac))).await?;
+
+    for action in script.actions {
+        match action {
+            Action::Send(message) => connection.send(message, false).await?,
+            Action::Replay(message) => {
+                connection.send(message.clone(), false).await?;
+                connection.sequence -= 1;
+                connection.send(message, false).await?;
+            }
+            Action::Receive => match connection.receive().await? {
+                Some(message) => connection.received.push(message),
+                None => return Ok(connection.received),
+            },
+            Action::Wait(duration) => tokio::time::sleep(duration).await,
+            Action::Close => return Ok(connection.received),
+        }
+    }
+    connection.drain().await
+}
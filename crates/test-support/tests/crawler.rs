@@ -0,0 +1,110 @@
+use std::net::{IpAddr, SocketAddr};
+use tokio::net::TcpListener;
+use handshake::crawler::{CrawlLimits, Crawler, PeerEdge, Reachability};
+use test_support::mock_peer::{Action, MockPeer, Script};
+use test_support::{keychain, node_config};
+use utils::strkey::encode_public_key;
+use xdr::compound_types::VarArray;
+use xdr::types::{PeerAddress, PeerAddressIp, StellarMessage};
+
+fn peers(addresses: &[SocketAddr]) -> StellarMessage {
+    let addresses = addresses.iter().map(|address| {
+        let IpAddr::V4(ip) = address.ip() else {
+            panic!("the mock peers listen on IPv4");
+        };
+        PeerAddress { ip: PeerAddressIp::IPv4(ip.octets()), port: address.port() as u32, num_failures: 1 }
+    });
+    StellarMessage::Peers(VarArray::new(addresses.collect()).unwrap())
+}
+
+async fn closed_port() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    listener.local_addr().unwrap()
+}
+
+/// a knows b and a closed port, b knows c, c knows nobody.
+async fn overlay() -> (MockPeer, MockPeer, MockPeer, SocketAddr) {
+    let closed = closed_port().await;
+    let c = MockPeer::start(Script::new().then(Action::Send(peers(&[])))).await.unwrap();
+    let b = MockPeer::start(Script::new().then(Action::Send(peers(&[c.address()])))).await.unwrap();
+    let a = MockPeer::start(Script::new().then(Action::Send(peers(&[b.address(), closed])))).await.unwrap();
+    (a, b, c, closed)
+}
+
+#[tokio::test]
+async fn crawls_the_overlay() {
+    let (a, b, c, closed) = overlay().await;
+    let node_config = node_config();
+    let keychain = keychain();
+    let graph = Crawler::new(&node_config, &keychain, CrawlLimits::default()).crawl(vec![a.address()]).await;
+
+    assert_eq!(graph.nodes.len(), 4);
+    for (peer, depth) in [(&a, 0), (&b, 1), (&c, 2)] {
+        let node = graph.node(&peer.address()).unwrap();
+        assert_eq!(node.depth, depth);
+        assert_eq!(node.reachability, Reachability::Reachable);
+        let info = node.peer.as_ref().unwrap();
+        assert_eq!(info.node_id, encode_public_key(peer.node_id().as_binary()));
+        assert_eq!(info.version_str, "mock-peer");
+    }
+    let unreachable = graph.node(&closed).unwrap();
+    assert_eq!(unreachable.reachability, Reachability::Unreachable);
+    assert!(unreachable.error.is_some());
+    assert_eq!(graph.edges, vec![
+        PeerEdge { from: a.address(), to: b.address(), num_failures: 1 },
+        PeerEdge { from: a.address(), to: closed, num_failures: 1 },
+        PeerEdge { from: b.address(), to: c.address(), num_failures: 1 },
+    ]);
+
+    // the mock peers record our GET_PEERS
+    for peer in [a, b, c] {
+        assert!(peer.finish().await.unwrap().contains(&StellarMessage::GetPeers));
+    }
+}
+
+#[tokio::test]
+async fn stops_at_the_depth_limit() {
+    let (a, b, c, _) = overlay().await;
+    let node_config = node_config();
+    let keychain = keychain();
+    let limits = CrawlLimits { max_depth: 1, ..CrawlLimits::default() };
+    let graph = Crawler::new(&node_config, &keychain, limits).crawl(vec![a.address()]).await;
+
+    assert_eq!(graph.node(&b.address()).unwrap().reachability, Reachability::Reachable);
+    let not_crawled = graph.node(&c.address()).unwrap();
+    assert_eq!(not_crawled.reachability, Reachability::NotCrawled);
+    assert!(not_crawled.peer.is_none());
+}
+
+#[tokio::test]
+async fn stops_at_the_count_limit() {
+    let (a, b, _, closed) = overlay().await;
+    let node_config = node_config();
+    let keychain = keychain();
+    let limits = CrawlLimits { max_peers: 2, ..CrawlLimits::default() };
+    let graph = Crawler::new(&node_config, &keychain, limits).crawl(vec![a.address()]).await;
+
+    let visited = graph.nodes.iter().filter(|node| node.reachability != Reachability::NotCrawled).count();
+    assert_eq!(visited, 2);
+    assert_eq!(graph.node(&b.address()).unwrap().reachability, Reachability::Reachable);
+    assert_eq!(graph.node(&closed).unwrap().reachability, Reachability::NotCrawled);
+}
+
+#[tokio::test]
+async fn exports_json_and_dot() {
+    let (a, b, _, closed) = overlay().await;
+    let node_config = node_config();
+    let keychain = keychain();
+    let limits = CrawlLimits { max_depth: 1, ..CrawlLimits::default() };
+    let graph = Crawler::new(&node_config, &keychain, limits).crawl(vec![a.address()]).await;
+
+    let json = graph.to_json().unwrap();
+    assert!(json.contains(&format!("\"address\": \"{}\"", a.address())));
+    assert!(json.contains("\"reachability\": \"unreachable\""));
+    assert!(json.contains(&format!("\"node_id\": \"{}\"", encode_public_key(b.node_id().as_binary()))));
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph overlay {"));
+    assert!(dot.contains(&format!("\"{}\" -> \"{}\";", a.address(), b.address())));
+    assert!(dot.contains(&format!("\"{}\" [label=\"{}\", color=red, fontcolor=red];", closed, closed)));
+}
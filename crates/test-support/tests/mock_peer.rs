@@ -0,0 +1,92 @@
+use futures::{SinkExt, StreamExt};
+use handshake::handshake::execute_handshake;
+use handshake::peer_handle::{Inbound, PeerHandle};
+use protocol::flow_control::PEER_FLOOD_READING_CAPACITY_BYTES;
+use test_support::mock_peer::{Action, MockPeer, Script};
+use test_support::{connect, handshake, keychain, node_config, scp_message, LEDGER_VERSION, OVERLAY_VERSION};
+use utils::misc::get_current_u64_milliseconds;
+use utils::strkey::encode_public_key;
+use xdr::compound_types::{VarArray, XdrArchive};
+use xdr::types::{AuthenticatedMessage, NodeId, PeerAddress, PeerAddressIp, SendMoreExtended, StellarMessage};
+
+fn peers() -> StellarMessage {
+    let address = PeerAddress { ip: PeerAddressIp::IPv4([127, 0, 0, 1]), port: 11625, num_failures: 0 };
+    StellarMessage::Peers(VarArray::new(vec![address]).unwrap())
+}
+
+#[tokio::test]
+async fn handshake_succeeds() {
+    let peer = MockPeer::start(Script::new().overlay_versions(30, 36)).await.unwrap();
+    let mut connection = connect(peer.address(), &node_config(), &keychain()).await.unwrap();
+    let peer_info = execute_handshake(&mut connection).await.unwrap();
+    assert_eq!(peer_info.node_id, encode_public_key(peer.node_id().as_binary()));
+    assert_eq!(peer_info.version_str, "mock-peer");
+    assert_eq!(peer_info.ledger_version, LEDGER_VERSION);
+    assert_eq!((peer_info.overlay_min_version, peer_info.overlay_version), (30, 36));
+    assert_eq!(peer_info.negotiated_overlay_version, OVERLAY_VERSION);
+    assert_eq!(peer_info.listening_port, 11625);
+    assert!(peer_info.cert_expiration > get_current_u64_milliseconds() / 1000);
+    assert_eq!(connection.peer_info(), Some(&peer_info));
+    drop(connection);
+    peer.finish().await.unwrap();
+}
+
+#[tokio::test]
+async fn exchanges_messages() {
+    let peer = MockPeer::start(Script::new().then(Action::Send(peers())).then(Action::Receive)).await.unwrap();
+    let mut connection = handshake(peer.address(), &node_config()).await.unwrap();
+    assert_eq!(connection.receive_message().await.unwrap(), Some(peers()));
+    connection.send_message(StellarMessage::GetPeers).await.unwrap();
+    drop(connection);
+    let received = peer.finish().await.unwrap();
+    // our SEND_MORE_EXTENDED granting the initial capacity comes first
+    assert!(matches!(received.as_slice(), [StellarMessage::SendMoreExtended(_), StellarMessage::GetPeers]));
+}
+
+#[tokio::test]
+async fn sends_flooded_message_once_granted() {
+    let grant = StellarMessage::SendMoreExtended(SendMoreExtended { num_messages: 1, num_bytes: PEER_FLOOD_READING_CAPACITY_BYTES });
+    let message = scp_message(NodeId::PublicKeyTypeEd25519([7; 32]), vec![1; 32]);
+    let peer = MockPeer::start(Script::new().then(Action::Send(grant))).await.unwrap();
+    let mut connection = handshake(peer.address(), &node_config()).await.unwrap();
+    connection.send_message(message.clone()).await.unwrap();
+    drop(connection);
+    assert!(peer.finish().await.unwrap().contains(&message));
+}
+
+#[tokio::test]
+async fn peer_handle_streams_messages() {
+    let script = Script::new()
+        .then(Action::Send(peers()))
+        .then(Action::Send(peers()))
+        .then(Action::Receive)
+        .then(Action::Receive)
+        .then(Action::Close);
+    let peer = MockPeer::start(script).await.unwrap();
+    let connection = handshake(peer.address(), &node_config()).await.unwrap();
+    let mut handle = PeerHandle::spawn(connection);
+    assert!(matches!(handle.next().await.unwrap().unwrap(), Inbound::Message(message) if message == peers()));
+    assert!(matches!(handle.next().await.unwrap().unwrap(), Inbound::Message(message) if message == peers()));
+    handle.sender().send(StellarMessage::GetPeers).await.unwrap();
+    assert!(handle.next().await.is_none());
+    let received = peer.finish().await.unwrap();
+    assert!(matches!(received.as_slice(), [StellarMessage::SendMoreExtended(_), StellarMessage::GetPeers]));
+}
+
+#[tokio::test]
+async fn rejects_a_wrong_mac() {
+    let peer = MockPeer::start(Script::new().then(Action::Receive)).await.unwrap();
+    let connection = handshake(peer.address(), &node_config()).await.unwrap();
+    let (mut session, mut framed, _, _) = connection.into_parts();
+    session.send(StellarMessage::GetPeers).unwrap();
+    while let Some(frame) = session.poll_transmit() {
+        let AuthenticatedMessage::V0(mut message) = frame.get_vec()[0].clone();
+        if message.message == StellarMessage::GetPeers {
+            message.mac.mac[0] ^= 0xff;
+        }
+        framed.send(XdrArchive::new(vec![AuthenticatedMessage::V0(message)])).await.unwrap();
+    }
+    drop(framed);
+    let error = peer.finish().await.unwrap_err();
+    assert!(error.to_string().contains("wrong MAC"), "{error}");
+}
@@ -0,0 +1,118 @@
+use futures::StreamExt;
+use handshake::peer_handle::{Inbound, PeerHandle};
+use protocol::errors::VerificationError;
+use protocol::scp::{sign_statement, signature_data, verify_envelope};
+use protocol::session::SessionEvent;
+use test_support::mock_peer::{Action, MockPeer, Script};
+use test_support::{handshake, keychain, node_config, scp_message, scp_statement, signed_scp_message, NETWORK};
+use utils::sha2::create_sha256;
+use utils::strkey::encode_public_key;
+use xdr::types::{EnvelopeType, NodeId, ScpStatementType, Signature, StellarMessage};
+use xdr::XdrCodec;
+
+/// Has a mock peer flood `message` and then send GET_PEERS. Returns why `message` was dropped,
+/// checking that the connection survived it.
+async fn receive_error(message: StellarMessage) -> VerificationError {
+    let script = Script::new().then(Action::Send(message)).then(Action::Send(StellarMessage::GetPeers));
+    let peer = MockPeer::start(script).await.unwrap();
+    let mut connection = handshake(peer.address(), &node_config()).await.unwrap();
+    let error = match connection.next_event().await.unwrap() {
+        Some(SessionEvent::InvalidMessage(_, error)) => error,
+        event => panic!("the SCP message must be rejected, got {:?}", event),
+    };
+    assert_eq!(connection.receive_message().await.unwrap(), Some(StellarMessage::GetPeers));
+    error
+}
+
+fn is_scp_signature_error(error: &VerificationError, node_id: &NodeId) -> bool {
+    matches!(error, VerificationError::ScpSignature { node_id: id, slot_index: 1 } if *id == encode_public_key(node_id.as_binary()))
+}
+
+#[tokio::test]
+async fn receives_signed_envelope() {
+    let keychain = keychain();
+    let message = signed_scp_message(&keychain, NETWORK, vec![1; 32]);
+    let peer = MockPeer::start(Script::new().then(Action::Send(message.clone()))).await.unwrap();
+    let mut connection = handshake(peer.address(), &node_config()).await.unwrap();
+    assert_eq!(connection.receive_message().await.unwrap(), Some(message));
+}
+
+#[tokio::test]
+async fn envelope_with_zero_signature() {
+    let node_id = NodeId::PublicKeyTypeEd25519(*keychain().persistent_public_key());
+    let error = receive_error(scp_message(node_id.clone(), vec![1; 32])).await;
+    assert!(is_scp_signature_error(&error, &node_id));
+}
+
+#[tokio::test]
+async fn envelope_with_truncated_signature() {
+    let keychain = keychain();
+    let StellarMessage::ScpMessage(mut envelope) = signed_scp_message(&keychain, NETWORK, vec![1; 32]) else {
+        unreachable!();
+    };
+    envelope.signature = Signature::new(envelope.signature.get_vec()[..32].to_vec()).unwrap();
+    let node_id = envelope.statement.node_id.clone();
+    let error = receive_error(StellarMessage::ScpMessage(envelope)).await;
+    assert!(is_scp_signature_error(&error, &node_id));
+}
+
+#[tokio::test]
+async fn envelope_signed_for_another_network() {
+    let keychain = keychain();
+    let message = signed_scp_message(&keychain, "Test SDF Network ; September 2015", vec![1; 32]);
+    let error = receive_error(message).await;
+    assert!(is_scp_signature_error(&error, &NodeId::PublicKeyTypeEd25519(*keychain.persistent_public_key())));
+}
+
+#[tokio::test]
+async fn envelope_signed_by_another_node() {
+    let node_id = NodeId::PublicKeyTypeEd25519(*keychain().persistent_public_key());
+    let network_id = create_sha256(NETWORK.as_bytes());
+    let envelope = sign_statement(&keychain(), &network_id, scp_statement(node_id.clone(), vec![1; 32])).unwrap();
+    let error = receive_error(StellarMessage::ScpMessage(envelope)).await;
+    assert!(is_scp_signature_error(&error, &node_id));
+}
+
+#[tokio::test]
+async fn receive_message_skips_invalid_envelopes() {
+    let node_id = NodeId::PublicKeyTypeEd25519(*keychain().persistent_public_key());
+    let script = Script::new()
+        .then(Action::Send(scp_message(node_id, vec![1; 32])))
+        .then(Action::Send(StellarMessage::GetPeers));
+    let peer = MockPeer::start(script).await.unwrap();
+    let mut connection = handshake(peer.address(), &node_config()).await.unwrap();
+    assert_eq!(connection.receive_message().await.unwrap(), Some(StellarMessage::GetPeers));
+}
+
+#[tokio::test]
+async fn peer_handle_reports_invalid_envelopes() {
+    let keychain = keychain();
+    let node_id = NodeId::PublicKeyTypeEd25519(*keychain.persistent_public_key());
+    let valid = signed_scp_message(&keychain, NETWORK, vec![1; 32]);
+    let script = Script::new()
+        .then(Action::Send(scp_message(node_id.clone(), vec![1; 32])))
+        .then(Action::Send(valid.clone()));
+    let peer = MockPeer::start(script).await.unwrap();
+    let mut handle = PeerHandle::spawn(handshake(peer.address(), &node_config()).await.unwrap());
+    match handle.next().await.unwrap().unwrap() {
+        Inbound::Invalid(StellarMessage::ScpMessage(_), error) => assert!(is_scp_signature_error(&error, &node_id)),
+        inbound => panic!("the SCP message must be rejected, got {:?}", inbound),
+    }
+    assert!(matches!(handle.next().await.unwrap().unwrap(), Inbound::Message(message) if message == valid));
+}
+
+#[test]
+fn signs_the_payload_unhashed() {
+    let keychain = keychain();
+    let network_id = create_sha256(NETWORK.as_bytes());
+    let statement = scp_statement(NodeId::PublicKeyTypeEd25519(*keychain.persistent_public_key()), vec![1; 32]);
+    assert_eq!(statement.pledges.statement_type(), ScpStatementType::ScpStExternalize);
+
+    let data = signature_data(&network_id, &statement);
+    assert_eq!(data, [network_id.to_vec(), EnvelopeType::EnvelopeTypeScp.to_xdr(), statement.to_xdr()].concat());
+
+    let mut envelope = sign_statement(&keychain, &network_id, statement).unwrap();
+    assert!(verify_envelope(&network_id, &envelope).is_ok());
+    envelope.statement.slot_index = 2;
+    assert!(matches!(verify_envelope(&network_id, &envelope), Err(VerificationError::ScpSignature { slot_index: 2, .. })));
+}
@@ -0,0 +1,129 @@
+use anyhow::Result;
+use protocol::connection_authentication::PeerRole;
+use protocol::errors::StellarError;
+use protocol::protocol::ProtocolMessage;
+use protocol::session::{Session, SessionEvent, SessionState};
+use protocol::stellar_protocol::StellarProtocol;
+use test_support::{keychain, node_config, protocol, stellar_error};
+use xdr::types::StellarMessage;
+use xdr::DecodeError;
+
+fn session(role: PeerRole) -> Session<StellarProtocol> {
+    Session::new(protocol(&node_config(), &keychain(), role))
+}
+
+/// The frames `from` wants to write, as bytes on the wire.
+fn transmits(from: &mut Session<StellarProtocol>) -> Vec<Vec<u8>> {
+    std::iter::from_fn(|| from.poll_transmit()).map(|frame| ProtocolMessage::to_xdr(&frame)).collect()
+}
+
+/// Delivers what `from` wants to write to `to`, one frame per `receive`.
+fn deliver(from: &mut Session<StellarProtocol>, to: &mut Session<StellarProtocol>) -> Result<()> {
+    transmits(from).iter().try_for_each(|frame| to.receive(frame))
+}
+
+#[test]
+fn handshake() {
+    let mut initiator = session(PeerRole::WeCalledRemote);
+    let mut responder = session(PeerRole::RemoteCalledUs);
+    deliver(&mut initiator, &mut responder).unwrap();
+    assert_eq!(responder.state(), SessionState::AwaitingAuth);
+    deliver(&mut responder, &mut initiator).unwrap();
+    assert_eq!(initiator.state(), SessionState::AwaitingAuth);
+    deliver(&mut initiator, &mut responder).unwrap();
+    assert_eq!(responder.state(), SessionState::Authenticated);
+    deliver(&mut responder, &mut initiator).unwrap();
+    assert_eq!(initiator.state(), SessionState::Authenticated);
+    for session in [&mut initiator, &mut responder] {
+        assert!(matches!(session.poll_event(), Some(SessionEvent::Authenticated)));
+        assert!(session.peer_info().is_some());
+    }
+
+    initiator.send(StellarMessage::GetPeers).unwrap();
+    deliver(&mut initiator, &mut responder).unwrap();
+    assert!(matches!(responder.poll_event(), Some(SessionEvent::Message(message)) if *message == StellarMessage::GetPeers));
+}
+
+#[test]
+fn frames_split_across_receives() {
+    let mut initiator = session(PeerRole::WeCalledRemote);
+    let mut responder = session(PeerRole::RemoteCalledUs);
+    let mut to_responder = true;
+    while initiator.state() != SessionState::Authenticated || responder.state() != SessionState::Authenticated {
+        let (from, to) = if to_responder { (&mut initiator, &mut responder) } else { (&mut responder, &mut initiator) };
+        // byte by byte
+        for byte in transmits(from).concat() {
+            to.receive(&[byte]).unwrap();
+        }
+        to_responder = !to_responder;
+    }
+    assert!(matches!(responder.poll_event(), Some(SessionEvent::Authenticated)));
+
+    // several frames in one receive, the last one cut in two
+    for _ in 0..3 {
+        initiator.send(StellarMessage::GetPeers).unwrap();
+    }
+    let bytes = transmits(&mut initiator).concat();
+    let (first, second) = bytes.split_at(bytes.len() - 5);
+    responder.receive(first).unwrap();
+    assert_eq!(std::iter::from_fn(|| responder.poll_event()).count(), 2);
+    responder.receive(second).unwrap();
+    assert_eq!(std::iter::from_fn(|| responder.poll_event()).count(), 1);
+}
+
+#[test]
+fn second_hello_is_illegal() {
+    let mut initiator = session(PeerRole::WeCalledRemote);
+    let mut responder = session(PeerRole::RemoteCalledUs);
+    let hello = transmits(&mut initiator).remove(0);
+    responder.receive(&hello).unwrap();
+    let error = responder.receive(&hello).unwrap_err();
+    assert!(matches!(
+        stellar_error(&error),
+        StellarError::IllegalTransition { state: SessionState::AwaitingAuth, message: "Hello" }
+    ));
+}
+
+#[test]
+fn auth_before_hello_is_illegal() {
+    let mut initiator = session(PeerRole::WeCalledRemote);
+    let mut responder = session(PeerRole::RemoteCalledUs);
+    deliver(&mut initiator, &mut responder).unwrap();
+    deliver(&mut responder, &mut initiator).unwrap();
+    let auth = transmits(&mut initiator).remove(0);
+
+    let mut other = session(PeerRole::RemoteCalledUs);
+    let error = other.receive(&auth).unwrap_err();
+    assert!(matches!(
+        stellar_error(&error),
+        StellarError::IllegalTransition { state: SessionState::AwaitingHello, message: "Auth" }
+    ));
+}
+
+#[test]
+fn closes_after_an_error() {
+    let mut initiator = session(PeerRole::WeCalledRemote);
+    let mut responder = session(PeerRole::RemoteCalledUs);
+    let hello = transmits(&mut initiator).remove(0);
+    responder.receive(&hello).unwrap();
+    assert!(responder.receive(&hello).is_err());
+
+    assert_eq!(responder.state(), SessionState::Closed);
+    let error = responder.receive(&hello).unwrap_err();
+    assert!(matches!(stellar_error(&error), StellarError::SessionClosed));
+    assert!(matches!(responder.send(StellarMessage::GetPeers), Err(StellarError::SessionClosed)));
+}
+
+#[test]
+fn rejects_frames_over_the_limit() {
+    let mut initiator = session(PeerRole::WeCalledRemote);
+    let mut responder = session(PeerRole::RemoteCalledUs).with_max_frame_size(64);
+    let hello = transmits(&mut initiator).remove(0);
+    // the header alone tells
+    let error = responder.receive(&hello[..4]).unwrap_err();
+    assert!(matches!(
+        stellar_error(&error),
+        StellarError::DecodeError(error) if matches!(error.0, DecodeError::RecordTooLarge { allowed_length: 64, .. })
+    ));
+    assert_eq!(responder.state(), SessionState::Closed);
+}
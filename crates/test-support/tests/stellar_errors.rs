@@ -0,0 +1,158 @@
+use std::time::Duration;
+use tokio::net::TcpListener;
+use protocol::connection_authentication::AuthenticationError;
+use protocol::errors::{StellarError, TimeoutPhase};
+use protocol::session::SessionState;
+use test_support::mock_peer::{Action, Misbehaviour, MockPeer, Script};
+use test_support::{connect, handshake, keychain, node_config, scp_message, stellar_error};
+use xdr::types::{ErrorCode, NodeId, SendMore, StellarMessage};
+
+/// Runs the handshake against a mock peer that is expected to fail it.
+async fn handshake_error(script: Script) -> anyhow::Error {
+    let peer = MockPeer::start(script).await.unwrap();
+    handshake(peer.address(), &node_config()).await.err().expect("the handshake must fail")
+}
+
+#[tokio::test]
+async fn expired_cert() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::ExpiredCert)).await;
+    assert!(matches!(stellar_error(&error), StellarError::AuthenticationError(AuthenticationError::VerificationCertExpired)));
+}
+
+#[tokio::test]
+async fn cert_signed_by_another_key() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::BadCertSignature)).await;
+    assert!(matches!(stellar_error(&error), StellarError::AuthenticationError(AuthenticationError::VerificationSignature)));
+}
+
+#[tokio::test]
+async fn frame_that_does_not_decode() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::Garbage)).await;
+    assert!(matches!(stellar_error(&error), StellarError::DecodeError(_)));
+}
+
+#[tokio::test]
+async fn nobody_listening() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+    let error = connect(address, &node_config(), &keychain()).await.err().unwrap();
+    assert!(matches!(error, StellarError::IOError(_)));
+}
+
+#[tokio::test]
+async fn truncated_frame() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::TruncatedFrame)).await;
+    assert!(matches!(stellar_error(&error), StellarError::ConnectionResetByPeer));
+}
+
+#[tokio::test]
+async fn hang_up_during_handshake() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::HangUp)).await;
+    assert!(matches!(stellar_error(&error), StellarError::ExpectedMoreMessages));
+}
+
+#[tokio::test]
+async fn message_before_hello() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::MessageBeforeHello)).await;
+    assert!(matches!(stellar_error(&error), StellarError::NotAuthenticated));
+}
+
+#[tokio::test]
+async fn send_before_handshake() {
+    let peer = MockPeer::start(Script::new()).await.unwrap();
+    let connection = connect(peer.address(), &node_config(), &keychain()).await.unwrap();
+    let (mut session, _, _, _) = connection.into_parts();
+    assert!(matches!(session.send(StellarMessage::GetPeers), Err(StellarError::NotAuthenticated)));
+}
+
+#[tokio::test]
+async fn authenticated_while_receiving() {
+    let peer = MockPeer::start(Script::new()).await.unwrap();
+    let mut connection = connect(peer.address(), &node_config(), &keychain()).await.unwrap();
+    let error = connection.receive_message().await.unwrap_err();
+    assert!(matches!(stellar_error(&error), StellarError::UnexpectedMessage));
+}
+
+#[tokio::test]
+async fn hello_twice() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::HelloTwice)).await;
+    assert!(matches!(stellar_error(&error), StellarError::IllegalTransition { state: SessionState::AwaitingAuth, .. }));
+}
+
+#[tokio::test]
+async fn send_after_close() {
+    let peer = MockPeer::start(Script::new().then(Action::Close)).await.unwrap();
+    let mut connection = handshake(peer.address(), &node_config()).await.unwrap();
+    assert!(connection.receive_message().await.unwrap().is_none());
+    let error = connection.send_message(StellarMessage::GetPeers).await.unwrap_err();
+    assert!(matches!(stellar_error(&error), StellarError::SessionClosed));
+}
+
+#[tokio::test]
+async fn flooded_message_without_capacity() {
+    let peer = MockPeer::start(Script::new()).await.unwrap();
+    let connection = handshake(peer.address(), &node_config()).await.unwrap();
+    let (mut session, _, _, _) = connection.into_parts();
+    let message = scp_message(peer.node_id().clone(), vec![1; 32]);
+    assert!(!session.can_send(&message));
+    assert!(matches!(session.send(message), Err(StellarError::NoCapacity)));
+}
+
+#[tokio::test]
+async fn flooded_message_over_capacity() {
+    let message = scp_message(NodeId::PublicKeyTypeEd25519([7; 32]), vec![1; 310_000]);
+    let peer = MockPeer::start(Script::new().then(Action::Send(message))).await.unwrap();
+    let mut connection = handshake(peer.address(), &node_config()).await.unwrap();
+    let error = connection.receive_message().await.unwrap_err();
+    assert!(matches!(stellar_error(&error), StellarError::FlowControlViolation));
+}
+
+#[tokio::test]
+async fn legacy_send_more() {
+    let message = StellarMessage::SendMore(SendMore { num_messages: 10 });
+    let peer = MockPeer::start(Script::new().then(Action::Send(message))).await.unwrap();
+    let mut connection = handshake(peer.address(), &node_config()).await.unwrap();
+    let error = connection.receive_message().await.unwrap_err();
+    assert!(matches!(stellar_error(&error), StellarError::UnsupportedSendMore));
+}
+
+#[tokio::test]
+async fn silent_peer() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::Silent)).await;
+    assert!(matches!(stellar_error(&error), StellarError::Timeout { phase: TimeoutPhase::Handshake }));
+}
+
+#[tokio::test]
+async fn idle_peer() {
+    let peer = MockPeer::start(Script::new().then(Action::Wait(Duration::from_secs(3)))).await.unwrap();
+    let mut connection = handshake(peer.address(), &node_config()).await.unwrap();
+    let error = connection.receive_message().await.unwrap_err();
+    assert!(matches!(stellar_error(&error), StellarError::Timeout { phase: TimeoutPhase::Idle }));
+    drop(connection);
+    // nothing but the initial grant while waiting, no GET_PEERS
+    assert!(matches!(peer.finish().await.unwrap().as_slice(), [StellarMessage::SendMoreExtended(_)]));
+}
+
+#[tokio::test]
+async fn error_msg_from_peer() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::ErrorMsg {
+        code: ErrorCode::ErrLoad,
+        message: "peer rejected".into(),
+    })).await;
+    match stellar_error(&error) {
+        StellarError::PeerError { code, message } => {
+            assert_eq!(*code, ErrorCode::ErrLoad);
+            assert_eq!(message, "peer rejected");
+        }
+        error => panic!("unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn invalid_config() {
+    let mut node_config = node_config();
+    node_config.node_info.overlay_min_version = node_config.node_info.overlay_version + 1;
+    let error = node_config.validate().unwrap_err();
+    assert!(matches!(error, StellarError::InvalidConfig { field, .. } if field == "node_info.overlay_min_version"));
+}
@@ -0,0 +1,78 @@
+#![cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+use handshake::handshake::connect;
+use handshake::unix_signer::{serve, UnixSocketSigner};
+use protocol::signer::{AsyncSigner, SignerError};
+use test_support::mock_peer::{MockPeer, Script};
+use test_support::{keychain, node_config, NETWORK};
+use utils::sha2::create_sha256;
+use utils::strkey::encode_public_key;
+use xdr::types::Curve25519Public;
+
+fn socket_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("signer-{}-{}.sock", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Serves a new keychain on a socket of its own for the standalone network.
+async fn start_signer(name: &str) -> (PathBuf, UnixSocketSigner) {
+    let path = socket_path(name);
+    let keychain = keychain();
+    let served = path.clone();
+    tokio::spawn(async move { serve(&served, keychain, create_sha256(NETWORK.as_bytes())).await });
+    for _ in 0..100 {
+        if let Ok(signer) = UnixSocketSigner::connect(&path).await {
+            return (path, signer);
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    panic!("the signer did not start");
+}
+
+#[tokio::test]
+async fn handshake_with_socket_signer() {
+    let (_, signer) = start_signer("handshake").await;
+    let peer = MockPeer::start(Script::new()).await.unwrap();
+    let (_, peer_info) = connect(peer.address(), &node_config(), &signer).await.unwrap();
+    assert_eq!(peer_info.node_id, encode_public_key(peer.node_id().as_binary()));
+}
+
+#[tokio::test]
+async fn refuses_certs_for_another_network() {
+    let (_, signer) = start_signer("network").await;
+    let network_id = create_sha256(b"Test SDF Network ; September 2015");
+    let result = signer.sign_auth_cert(&network_id, 1, &Curve25519Public { key: [1; 32] }).await;
+    assert!(matches!(result, Err(SignerError::Refused(_))));
+}
+
+#[tokio::test]
+async fn refuses_to_sign_a_bare_hash() {
+    let (path, _) = start_signer("hash").await;
+    let mut stream = UnixStream::connect(&path).await.unwrap();
+    // operation 1 with the 32 bytes of a hash instead of an auth cert
+    stream.write_all(&[&[1, 0, 0, 0, 32][..], &[7; 32]].concat()).await.unwrap();
+    assert_eq!(stream.read_u8().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn socket_is_private() {
+    let (path, _) = start_signer("private").await;
+    assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+}
+
+#[tokio::test]
+async fn refuses_an_existing_socket() {
+    let (path, _) = start_signer("running").await;
+    let error = serve(&path, keychain(), [0; 32]).await.unwrap_err();
+    assert!(error.to_string().contains("a signer is listening on it already"), "{}", error);
+
+    let path = socket_path("stale");
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    let error = serve(&path, keychain(), [0; 32]).await.unwrap_err();
+    assert!(error.to_string().contains("left over"), "{}", error);
+    std::fs::remove_file(&path).unwrap();
+}
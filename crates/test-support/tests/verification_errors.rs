@@ -0,0 +1,92 @@
+use handshake::handshake::execute_handshake;
+use protocol::connection_authentication::AuthenticationError;
+use protocol::errors::{StellarError, VerificationError};
+use test_support::mock_peer::{Action, Misbehaviour, MockPeer, Script};
+use test_support::{connect, handshake, keychain, node_config, stellar_error};
+use xdr::types::{NodeId, StellarMessage};
+
+/// Runs the handshake against a mock peer that is expected to fail it.
+async fn handshake_error(script: Script) -> anyhow::Error {
+    let peer = MockPeer::start(script).await.unwrap();
+    handshake(peer.address(), &node_config()).await.err().expect("the handshake must fail")
+}
+
+#[tokio::test]
+async fn auth_with_wrong_sequence() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::WrongSequence)).await;
+    assert!(matches!(stellar_error(&error), StellarError::Verification(VerificationError::SequenceMismatch)));
+}
+
+#[tokio::test]
+async fn replayed_message() {
+    let peer = MockPeer::start(Script::new().then(Action::Replay(StellarMessage::GetPeers))).await.unwrap();
+    let mut connection = handshake(peer.address(), &node_config()).await.unwrap();
+    assert!(matches!(connection.receive_message().await.unwrap(), Some(StellarMessage::GetPeers)));
+    let error = connection.receive_message().await.unwrap_err();
+    assert!(matches!(stellar_error(&error), StellarError::Verification(VerificationError::SequenceMismatch)));
+}
+
+#[tokio::test]
+async fn auth_with_bad_mac() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::BadMac)).await;
+    assert!(matches!(stellar_error(&error), StellarError::Verification(VerificationError::MacKey)));
+}
+
+#[tokio::test]
+async fn hello_for_another_network() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::WrongNetworkId)).await;
+    assert!(matches!(stellar_error(&error), StellarError::Verification(VerificationError::NetworkIdMismatch)));
+}
+
+#[tokio::test]
+async fn overlay_versions_without_overlap() {
+    let error = handshake_error(Script::new().overlay_versions(10, 20)).await;
+    assert!(matches!(stellar_error(&error), StellarError::Verification(VerificationError::OverlayVersionMismatch { remote_min: 10, remote_max: 20 })));
+}
+
+#[tokio::test]
+async fn connected_to_self() {
+    let keychain = keychain();
+    let peer = MockPeer::start(Script::new().keychain(keychain.clone())).await.unwrap();
+    let mut connection = connect(peer.address(), &node_config(), &keychain).await.unwrap();
+    let error = execute_handshake(&mut connection).await.unwrap_err();
+    assert!(matches!(stellar_error(&error), StellarError::Verification(VerificationError::ConnectedToSelf)));
+}
+
+#[tokio::test]
+async fn reflected_nonce() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::ReflectNonce)).await;
+    assert!(matches!(stellar_error(&error), StellarError::Verification(VerificationError::NonceReused)));
+}
+
+#[tokio::test]
+async fn denied_peer() {
+    let peer = MockPeer::start(Script::new()).await.unwrap();
+    let mut node_config = node_config();
+    node_config.denied_peers = vec![peer.node_id().clone()];
+    let error = handshake(peer.address(), &node_config).await.err().unwrap();
+    assert!(matches!(stellar_error(&error), StellarError::Verification(VerificationError::PeerDenied)));
+}
+
+#[tokio::test]
+async fn peer_not_allowed() {
+    let peer = MockPeer::start(Script::new()).await.unwrap();
+    let mut node_config = node_config();
+    node_config.allowed_peers = Some(vec![NodeId::PublicKeyTypeEd25519(*keychain().persistent_public_key())]);
+    let error = handshake(peer.address(), &node_config).await.err().unwrap();
+    assert!(matches!(stellar_error(&error), StellarError::Verification(VerificationError::PeerNotAllowed)));
+}
+
+#[tokio::test]
+async fn allowed_peer() {
+    let peer = MockPeer::start(Script::new()).await.unwrap();
+    let mut node_config = node_config();
+    node_config.allowed_peers = Some(vec![peer.node_id().clone()]);
+    handshake(peer.address(), &node_config).await.unwrap();
+}
+
+#[tokio::test]
+async fn cert_with_short_signature() {
+    let error = handshake_error(Script::new().misbehave(Misbehaviour::ShortCertSignature)).await;
+    assert!(matches!(stellar_error(&error), StellarError::AuthenticationError(AuthenticationError::VerificationSignature)));
+}
@@ -1,2 +1,3 @@
 pub mod sha2;
-pub mod misc;
\ No newline at end of file
+pub mod misc;
+pub mod strkey;
\ No newline at end of file
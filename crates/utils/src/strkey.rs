@@ -0,0 +1,337 @@
+//! Stellar's StrKey encoding of keys and hashes: a version byte, the data and a CRC16-XModem checksum,
+//! base32 encoded without padding, as described in SEP-23.
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use data_encoding::BASE32_NOPAD;
+use crate::sha2::Uint256;
+
+/// Longest payload of a signed payload signer.
+pub const MAX_SIGNED_PAYLOAD_LENGTH: usize = 64;
+
+/// The version byte, which also determines the first character of the encoded key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Version {
+    /// `G...`, an account or node id.
+    PublicKeyEd25519 = 6 << 3,
+    /// `S...`, the seed of an ed25519 key.
+    PrivateKeyEd25519 = 18 << 3,
+    /// `T...`, the hash of a pre-authorized transaction.
+    PreAuthTx = 19 << 3,
+    /// `X...`, a sha256 hash whose preimage signs.
+    HashX = 23 << 3,
+    /// `M...`, an account with a 64-bit id.
+    MuxedAccountEd25519 = 12 << 3,
+    /// `P...`, an ed25519 signer of a payload.
+    SignedPayloadEd25519 = 15 << 3,
+}
+
+impl TryFrom<u8> for Version {
+    type Error = StrKeyError;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        [
+            Version::PublicKeyEd25519,
+            Version::PrivateKeyEd25519,
+            Version::PreAuthTx,
+            Version::HashX,
+            Version::MuxedAccountEd25519,
+            Version::SignedPayloadEd25519,
+        ]
+        .into_iter()
+        .find(|version| *version as u8 == byte)
+        .ok_or(StrKeyError::UnknownVersion(byte))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StrKeyError {
+    #[error("Not a base32 string without padding")]
+    InvalidBase32,
+    #[error("Too short for a version byte and a checksum")]
+    TooShort,
+    #[error("Unknown version byte {0:#04x}")]
+    UnknownVersion(u8),
+    #[error("Expected a {expected:?} key, found {found:?}")]
+    UnexpectedVersion { expected: Version, found: Version },
+    #[error("Checksum mismatch")]
+    InvalidChecksum,
+    #[error("{length} bytes of data do not fit a {version:?} key")]
+    InvalidLength { version: Version, length: usize },
+    #[error("Signed payload of {0} bytes, at most 64 are allowed")]
+    PayloadTooLong(usize),
+    #[error("Non-zero padding after the signed payload")]
+    InvalidPadding,
+}
+
+/// A decoded strkey.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrKey {
+    PublicKeyEd25519(Uint256),
+    PrivateKeyEd25519(Uint256),
+    PreAuthTx(Uint256),
+    HashX(Uint256),
+    MuxedAccountEd25519 { ed25519: Uint256, id: u64 },
+    SignedPayloadEd25519 { ed25519: Uint256, payload: Vec<u8> },
+}
+
+impl StrKey {
+    pub fn version(&self) -> Version {
+        match self {
+            StrKey::PublicKeyEd25519(_) => Version::PublicKeyEd25519,
+            StrKey::PrivateKeyEd25519(_) => Version::PrivateKeyEd25519,
+            StrKey::PreAuthTx(_) => Version::PreAuthTx,
+            StrKey::HashX(_) => Version::HashX,
+            StrKey::MuxedAccountEd25519 { .. } => Version::MuxedAccountEd25519,
+            StrKey::SignedPayloadEd25519 { .. } => Version::SignedPayloadEd25519,
+        }
+    }
+
+    /// Decodes any kind of strkey, verifying the version byte, the checksum and the length of the data.
+    pub fn decode(key: &str) -> Result<Self, StrKeyError> {
+        let decoded = BASE32_NOPAD.decode(key.as_bytes()).map_err(|_| StrKeyError::InvalidBase32)?;
+        if decoded.len() < 3 {
+            return Err(StrKeyError::TooShort);
+        }
+        let (payload, checksum) = decoded.split_at(decoded.len() - 2);
+        if checksum != crc16(payload).to_le_bytes() {
+            return Err(StrKeyError::InvalidChecksum);
+        }
+        let version = Version::try_from(payload[0])?;
+        let data = &payload[1..];
+        let invalid_length = StrKeyError::InvalidLength { version, length: data.len() };
+        match version {
+            Version::PublicKeyEd25519 => uint256(data, version).map(StrKey::PublicKeyEd25519),
+            Version::PrivateKeyEd25519 => uint256(data, version).map(StrKey::PrivateKeyEd25519),
+            Version::PreAuthTx => uint256(data, version).map(StrKey::PreAuthTx),
+            Version::HashX => uint256(data, version).map(StrKey::HashX),
+            Version::MuxedAccountEd25519 => {
+                if data.len() != 40 {
+                    return Err(invalid_length);
+                }
+                let (ed25519, id) = data.split_at(32);
+                Ok(StrKey::MuxedAccountEd25519 {
+                    ed25519: uint256(ed25519, version)?,
+                    id: u64::from_be_bytes(id.try_into().map_err(|_| invalid_length)?),
+                })
+            }
+            Version::SignedPayloadEd25519 => {
+                if data.len() < 32 + 4 + 4 || data.len() > 32 + 4 + MAX_SIGNED_PAYLOAD_LENGTH {
+                    return Err(invalid_length);
+                }
+                let (ed25519, rest) = data.split_at(32);
+                let (length, rest) = rest.split_at(4);
+                let length = u32::from_be_bytes(length.try_into().map_err(|_| invalid_length.clone())?) as usize;
+                if length > MAX_SIGNED_PAYLOAD_LENGTH {
+                    return Err(StrKeyError::PayloadTooLong(length));
+                }
+                if rest.len() != padded_length(length) {
+                    return Err(invalid_length);
+                }
+                let (payload, padding) = rest.split_at(length);
+                if padding.iter().any(|byte| *byte != 0) {
+                    return Err(StrKeyError::InvalidPadding);
+                }
+                Ok(StrKey::SignedPayloadEd25519 { ed25519: uint256(ed25519, version)?, payload: payload.to_vec() })
+            }
+        }
+    }
+
+    /// Decodes a strkey that must have the given version.
+    pub fn decode_as(key: &str, expected: Version) -> Result<Self, StrKeyError> {
+        let decoded = StrKey::decode(key)?;
+        match decoded.version() {
+            found if found == expected => Ok(decoded),
+            found => Err(StrKeyError::UnexpectedVersion { expected, found }),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let mut payload = vec![self.version() as u8];
+        match self {
+            StrKey::PublicKeyEd25519(data)
+            | StrKey::PrivateKeyEd25519(data)
+            | StrKey::PreAuthTx(data)
+            | StrKey::HashX(data) => payload.extend_from_slice(data),
+            StrKey::MuxedAccountEd25519 { ed25519, id } => {
+                payload.extend_from_slice(ed25519);
+                payload.extend_from_slice(&id.to_be_bytes());
+            }
+            StrKey::SignedPayloadEd25519 { ed25519, payload: signed } => {
+                payload.extend_from_slice(ed25519);
+                payload.extend_from_slice(&(signed.len() as u32).to_be_bytes());
+                payload.extend_from_slice(signed);
+                payload.resize(payload.len() + padded_length(signed.len()) - signed.len(), 0);
+            }
+        }
+        let checksum = crc16(&payload);
+        payload.extend_from_slice(&checksum.to_le_bytes());
+        BASE32_NOPAD.encode(&payload)
+    }
+}
+
+impl Display for StrKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.encode())
+    }
+}
+
+impl FromStr for StrKey {
+    type Err = StrKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StrKey::decode(s)
+    }
+}
+
+/// Decodes a `G...` key.
+pub fn decode_public_key(key: &str) -> Result<Uint256, StrKeyError> {
+    match StrKey::decode_as(key, Version::PublicKeyEd25519)? {
+        StrKey::PublicKeyEd25519(data) => Ok(data),
+        _ => unreachable!("the version was checked"),
+    }
+}
+
+/// Decodes an `S...` seed.
+pub fn decode_seed(key: &str) -> Result<Uint256, StrKeyError> {
+    match StrKey::decode_as(key, Version::PrivateKeyEd25519)? {
+        StrKey::PrivateKeyEd25519(data) => Ok(data),
+        _ => unreachable!("the version was checked"),
+    }
+}
+
+pub fn encode_public_key(data: &Uint256) -> String {
+    StrKey::PublicKeyEd25519(*data).encode()
+}
+
+pub fn encode_seed(data: &Uint256) -> String {
+    StrKey::PrivateKeyEd25519(*data).encode()
+}
+
+fn uint256(data: &[u8], version: Version) -> Result<Uint256, StrKeyError> {
+    data.try_into().map_err(|_| StrKeyError::InvalidLength { version, length: data.len() })
+}
+
+/// The signed payload is padded to a multiple of 4 bytes.
+fn padded_length(length: usize) -> usize {
+    length.div_ceil(4) * 4
+}
+
+fn crc16(payload: &[u8]) -> u16 {
+    let mut crc16: u16 = 0x0;
+    for &byte in payload.iter() {
+        let lookup_index = ((crc16 >> 8) ^ (byte as u16)) as usize;
+        crc16 = (crc16 << 8) ^ CRC16_XMODEM_TABLE[lookup_index];
+    }
+    crc16
+}
+
+const CRC16_XMODEM_TABLE: [u16; 256] = [0x0000, 0x1021, 0x2042, 0x3063, 0x4084, 0x50a5, 0x60c6, 0x70e7, 0x8108, 0x9129, 0xa14a, 0xb16b, 0xc18c, 0xd1ad, 0xe1ce, 0xf1ef, 0x1231, 0x0210, 0x3273, 0x2252, 0x52b5, 0x4294, 0x72f7, 0x62d6, 0x9339, 0x8318, 0xb37b, 0xa35a, 0xd3bd, 0xc39c, 0xf3ff, 0xe3de, 0x2462, 0x3443, 0x0420, 0x1401, 0x64e6, 0x74c7, 0x44a4, 0x5485, 0xa56a, 0xb54b, 0x8528, 0x9509, 0xe5ee, 0xf5cf, 0xc5ac, 0xd58d, 0x3653, 0x2672, 0x1611, 0x0630, 0x76d7, 0x66f6, 0x5695, 0x46b4, 0xb75b, 0xa77a, 0x9719, 0x8738, 0xf7df, 0xe7fe, 0xd79d, 0xc7bc, 0x48c4, 0x58e5, 0x6886, 0x78a7, 0x0840, 0x1861, 0x2802, 0x3823, 0xc9cc, 0xd9ed, 0xe98e, 0xf9af, 0x8948, 0x9969, 0xa90a, 0xb92b, 0x5af5, 0x4ad4, 0x7ab7, 0x6a96, 0x1a71, 0x0a50, 0x3a33, 0x2a12, 0xdbfd, 0xcbdc, 0xfbbf, 0xeb9e, 0x9b79, 0x8b58, 0xbb3b, 0xab1a, 0x6ca6, 0x7c87, 0x4ce4, 0x5cc5, 0x2c22, 0x3c03, 0x0c60, 0x1c41, 0xedae, 0xfd8f, 0xcdec, 0xddcd, 0xad2a, 0xbd0b, 0x8d68, 0x9d49, 0x7e97, 0x6eb6, 0x5ed5, 0x4ef4, 0x3e13, 0x2e32, 0x1e51, 0x0e70, 0xff9f, 0xefbe, 0xdfdd, 0xcffc, 0xbf1b, 0xaf3a, 0x9f59, 0x8f78, 0x9188, 0x81a9, 0xb1ca, 0xa1eb, 0xd10c, 0xc12d, 0xf14e, 0xe16f, 0x1080, 0x00a1, 0x30c2, 0x20e3, 0x5004, 0x4025, 0x7046, 0x6067, 0x83b9, 0x9398, 0xa3fb, 0xb3da, 0xc33d, 0xd31c, 0xe37f, 0xf35e, 0x02b1, 0x1290, 0x22f3, 0x32d2, 0x4235, 0x5214, 0x6277, 0x7256, 0xb5ea, 0xa5cb, 0x95a8, 0x8589, 0xf56e, 0xe54f, 0xd52c, 0xc50d, 0x34e2, 0x24c3, 0x14a0, 0x0481, 0x7466, 0x6447, 0x5424, 0x4405, 0xa7db, 0xb7fa, 0x8799, 0x97b8, 0xe75f, 0xf77e, 0xc71d, 0xd73c, 0x26d3, 0x36f2, 0x0691, 0x16b0, 0x6657, 0x7676, 0x4615, 0x5634, 0xd94c, 0xc96d, 0xf90e, 0xe92f, 0x99c8, 0x89e9, 0xb98a, 0xa9ab, 0x5844, 0x4865, 0x7806, 0x6827, 0x18c0, 0x08e1, 0x3882, 0x28a3, 0xcb7d, 0xdb5c, 0xeb3f, 0xfb1e, 0x8bf9, 0x9bd8, 0xabbb, 0xbb9a, 0x4a75, 0x5a54, 0x6a37, 0x7a16, 0x0af1, 0x1ad0, 0x2ab3, 0x3a92, 0xfd2e, 0xed0f, 0xdd6c, 0xcd4d, 0xbdaa, 0xad8b, 0x9de8, 0x8dc9, 0x7c26, 0x6c07, 0x5c64, 0x4c45, 0x3ca2, 0x2c83, 0x1ce0, 0x0cc1, 0xef1f, 0xff3e, 0xcf5d, 0xdf7c, 0xaf9b, 0xbfba, 0x8fd9, 0x9ff8, 0x6e17, 0x7e36, 0x4e55, 0x5e74, 0x2e93, 0x3eb2, 0x0ed1, 0x1ef0];
+
+#[cfg(test)]
+mod tests {
+    use data_encoding::HEXLOWER;
+    use super::*;
+
+    // test vectors of SEP-23
+    const PUBLIC_KEY: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+    const ED25519: &str = "3f0c34bf93ad0d9971d04ccc90f705511c838aad9734a4a2fb0d7a03fc7fe89a";
+    const SEED: &str = "SBU2RRGLXH3E5CQHTD3ODLDF2BWDCYUSSBLLZ5GNW7JXHDIYKXZWHOKR";
+    const PRE_AUTH_TX: &str = "TBU2RRGLXH3E5CQHTD3ODLDF2BWDCYUSSBLLZ5GNW7JXHDIYKXZWHXL7";
+    const HASH_X: &str = "XBU2RRGLXH3E5CQHTD3ODLDF2BWDCYUSSBLLZ5GNW7JXHDIYKXZWGTOG";
+    const HASH: &str = "69a8c4cbb9f64e8a0798f6e1ac65d06c3162929056bcf4cdb7d3738d1855f363";
+    const MUXED_ID_0: &str = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAAACJUQ";
+    const MUXED_ID_MIN: &str = "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVAAAAAAAAAAAAAJLK";
+    const SIGNED_PAYLOAD: &str = "PA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAQACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB6IBZGM";
+
+    fn uint256(hex: &str) -> Uint256 {
+        HEXLOWER.decode(hex.as_bytes()).unwrap().try_into().unwrap()
+    }
+
+    /// `payload`, version byte included, with its checksum.
+    fn encode_raw(payload: &[u8]) -> String {
+        BASE32_NOPAD.encode(&[payload, &crc16(payload).to_le_bytes()].concat())
+    }
+
+    fn assert_round_trip(encoded: &str, key: StrKey) {
+        assert_eq!(StrKey::decode(encoded).unwrap(), key);
+        assert_eq!(key.encode(), encoded);
+    }
+
+    #[test]
+    fn round_trips_the_sep_23_vectors() {
+        assert_round_trip(PUBLIC_KEY, StrKey::PublicKeyEd25519(uint256(ED25519)));
+        assert_round_trip(SEED, StrKey::PrivateKeyEd25519(uint256(HASH)));
+        assert_round_trip(PRE_AUTH_TX, StrKey::PreAuthTx(uint256(HASH)));
+        assert_round_trip(HASH_X, StrKey::HashX(uint256(HASH)));
+        assert_round_trip(MUXED_ID_0, StrKey::MuxedAccountEd25519 { ed25519: uint256(ED25519), id: 0 });
+        assert_round_trip(MUXED_ID_MIN, StrKey::MuxedAccountEd25519 { ed25519: uint256(ED25519), id: 1 << 63 });
+        assert_round_trip(SIGNED_PAYLOAD, StrKey::SignedPayloadEd25519 { ed25519: uint256(ED25519), payload: (1..=32).collect() });
+
+        assert_eq!(decode_public_key(PUBLIC_KEY).unwrap(), uint256(ED25519));
+        assert_eq!(encode_public_key(&uint256(ED25519)), PUBLIC_KEY);
+        assert_eq!(decode_seed(SEED).unwrap(), uint256(HASH));
+        assert_eq!(encode_seed(&uint256(HASH)), SEED);
+    }
+
+    #[test]
+    fn pads_signed_payloads() {
+        for length in [1, 4, 29, 64] {
+            let key = StrKey::SignedPayloadEd25519 { ed25519: uint256(ED25519), payload: vec![7; length] };
+            assert_eq!(StrKey::decode(&key.encode()).unwrap(), key);
+        }
+    }
+
+    #[test]
+    fn rejects_a_wrong_checksum() {
+        let mut key = PUBLIC_KEY.to_string();
+        key.replace_range(10..11, "A");
+        assert_eq!(StrKey::decode(&key), Err(StrKeyError::InvalidChecksum));
+        assert_eq!(StrKey::decode(&PUBLIC_KEY[..PUBLIC_KEY.len() - 1]), Err(StrKeyError::InvalidBase32));
+        assert_eq!(StrKey::decode("AAAA"), Err(StrKeyError::TooShort));
+    }
+
+    #[test]
+    fn rejects_a_wrong_version_byte() {
+        let data = uint256(ED25519);
+        assert_eq!(StrKey::decode(&encode_raw(&[&[0xff], &data[..]].concat())), Err(StrKeyError::UnknownVersion(0xff)));
+        assert_eq!(
+            decode_public_key(SEED),
+            Err(StrKeyError::UnexpectedVersion { expected: Version::PublicKeyEd25519, found: Version::PrivateKeyEd25519 })
+        );
+        assert!(matches!(decode_seed(MUXED_ID_0), Err(StrKeyError::UnexpectedVersion { .. })));
+    }
+
+    #[test]
+    fn rejects_wrong_lengths() {
+        let data = uint256(ED25519);
+        let public_key = [&[Version::PublicKeyEd25519 as u8], &data[..31]].concat();
+        assert_eq!(
+            StrKey::decode(&encode_raw(&public_key)),
+            Err(StrKeyError::InvalidLength { version: Version::PublicKeyEd25519, length: 31 })
+        );
+
+        let version = Version::MuxedAccountEd25519;
+        for id_length in [7, 9] {
+            let muxed = [&[version as u8], &data[..], &vec![0; id_length]].concat();
+            assert_eq!(StrKey::decode(&encode_raw(&muxed)), Err(StrKeyError::InvalidLength { version, length: 32 + id_length }));
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_signed_payloads() {
+        let version = Version::SignedPayloadEd25519;
+        let signed_payload = |length: u32, payload: &[u8]| {
+            encode_raw(&[&[version as u8], &uint256(ED25519)[..], &length.to_be_bytes(), payload].concat())
+        };
+        // too short for any payload, too long for the longest one
+        assert_eq!(StrKey::decode(&signed_payload(0, &[])), Err(StrKeyError::InvalidLength { version, length: 36 }));
+        assert_eq!(StrKey::decode(&signed_payload(64, &[0; 68])), Err(StrKeyError::InvalidLength { version, length: 104 }));
+        // the length says 5 bytes, so they take 8 with the padding
+        assert_eq!(StrKey::decode(&signed_payload(5, &[1; 4])), Err(StrKeyError::InvalidLength { version, length: 40 }));
+        assert_eq!(StrKey::decode(&signed_payload(5, &[1; 12])), Err(StrKeyError::InvalidLength { version, length: 48 }));
+        assert_eq!(StrKey::decode(&signed_payload(65, &[1; 64])), Err(StrKeyError::PayloadTooLong(65)));
+        assert_eq!(StrKey::decode(&signed_payload(5, &[1, 1, 1, 1, 1, 0, 0, 1])), Err(StrKeyError::InvalidPadding));
+        assert!(StrKey::decode(&signed_payload(5, &[1, 1, 1, 1, 1, 0, 0, 0])).is_ok());
+    }
+}
@@ -0,0 +1,80 @@
+/// Items of an XDR specification file (RFC 4506, section 6), with the `namespace` wrapper dropped.
+#[derive(Debug, Clone)]
+pub enum Definition {
+    Const { name: String, value: i64 },
+    Typedef(Declaration),
+    Enum { name: String, members: Vec<EnumMember> },
+    Struct { name: String, fields: Vec<Declaration> },
+    Union { name: String, body: UnionBody },
+}
+
+impl Definition {
+    pub fn name(&self) -> &str {
+        match self {
+            Definition::Const { name, .. }
+            | Definition::Enum { name, .. }
+            | Definition::Struct { name, .. }
+            | Definition::Union { name, .. } => name,
+            Definition::Typedef(declaration) => &declaration.name,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumMember {
+    pub name: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct Declaration {
+    pub name: String,
+    pub kind: DeclarationKind,
+}
+
+#[derive(Debug, Clone)]
+pub enum DeclarationKind {
+    Void,
+    Plain(TypeSpec),
+    FixedArray(TypeSpec, Value),
+    VarArray(TypeSpec, Option<Value>),
+    FixedOpaque(Value),
+    VarOpaque(Option<Value>),
+    String(Option<Value>),
+    Optional(TypeSpec),
+}
+
+#[derive(Debug, Clone)]
+pub enum TypeSpec {
+    Int,
+    UnsignedInt,
+    Hyper,
+    UnsignedHyper,
+    Bool,
+    Named(String),
+    Enum(Vec<EnumMember>),
+    Struct(Vec<Declaration>),
+    Union(Box<UnionBody>),
+}
+
+#[derive(Debug, Clone)]
+pub struct UnionBody {
+    pub discriminant: Declaration,
+    pub arms: Vec<UnionArm>,
+    pub default: Option<Declaration>,
+    /// Where `switch` is, for errors.
+    pub line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnionArm {
+    pub cases: Vec<Value>,
+    pub declaration: Declaration,
+}
+
+/// A constant: either a literal or a reference to a `const`/enum member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Literal(i64),
+    Named(String),
+}
@@ -0,0 +1,18 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CodegenError {
+    #[error("Unexpected character '{character}' at line {line}")]
+    UnexpectedCharacter { character: char, line: usize },
+    #[error("Invalid number {literal} at line {line}")]
+    InvalidNumber { literal: String, line: usize },
+    #[error("Expected {expected}, found {found} at line {line}")]
+    UnexpectedToken { expected: String, found: String, line: usize },
+    #[error("Type {name} at line {line} is not supported")]
+    UnsupportedType { name: String, line: usize },
+    #[error("Unknown type or constant {0}")]
+    UnknownName(String),
+    #[error("Failed to read {path}: {source}")]
+    Io { path: String, source: io::Error },
+}
@@ -0,0 +1,310 @@
+use std::fmt::Write;
+
+use crate::ast::*;
+use crate::errors::CodegenError;
+use crate::naming::{field_name, type_name};
+use crate::schema::Schema;
+
+pub struct Generator<'a> {
+    schema: &'a Schema,
+}
+
+struct Field {
+    name: String,
+    ty: String,
+}
+
+enum Discriminant {
+    Int(&'static str),
+    Enum(String, Vec<EnumMember>),
+}
+
+impl<'a> Generator<'a> {
+    pub fn new(schema: &'a Schema) -> Self {
+        Self { schema }
+    }
+
+    pub fn constants(&self) -> String {
+        let mut output = String::new();
+        for definition in &self.schema.definitions {
+            if let Definition::Const { name, value } = definition {
+                writeln!(output, "pub const {}: i32 = {};", name, value).unwrap();
+            }
+        }
+        output
+    }
+
+    /// Generates the given types, or all of them if `names` is `None`.
+    pub fn types(&self, names: Option<&[String]>) -> Result<String, CodegenError> {
+        let mut output = String::new();
+        for definition in &self.schema.definitions {
+            let name = type_name(definition.name());
+            if names.is_some_and(|names| !names.contains(&name)) {
+                continue;
+            }
+            let code = match definition {
+                Definition::Const { .. } => continue,
+                Definition::Typedef(declaration) => self.typedef(&name, declaration)?,
+                Definition::Enum { members, .. } => self.enumeration(&name, members)?,
+                Definition::Struct { fields, .. } => self.structure(&name, fields)?,
+                Definition::Union { body, .. } => self.union(&name, body)?,
+            };
+            // runs of typedefs are kept together, everything else is separated by a blank line
+            let continues_typedefs = code.starts_with("pub type") && output.ends_with(";\n");
+            if !output.is_empty() && !continues_typedefs {
+                output.push('\n');
+            }
+            output.push_str(&code);
+        }
+        Ok(output)
+    }
+
+    fn size(&self, value: &Value) -> Result<String, CodegenError> {
+        match value {
+            Value::Named(name) if self.schema.is_constant(name) => Ok(name.clone()),
+            _ => Ok(self.schema.resolve_value(value)?.to_string()),
+        }
+    }
+
+    fn spec(&self, spec: &TypeSpec) -> String {
+        match spec {
+            TypeSpec::Int => "i32".into(),
+            TypeSpec::UnsignedInt => "u32".into(),
+            TypeSpec::Hyper => "i64".into(),
+            TypeSpec::UnsignedHyper => "u64".into(),
+            TypeSpec::Bool => "bool".into(),
+            TypeSpec::Named(name) => type_name(name),
+            TypeSpec::Enum(_) | TypeSpec::Struct(_) | TypeSpec::Union(_) => unreachable!("nested definitions are lifted"),
+        }
+    }
+
+    fn field(&self, owner: &str, declaration: &Declaration) -> Result<Field, CodegenError> {
+        let name = field_name(&declaration.name);
+        let ty = match &declaration.kind {
+            DeclarationKind::Void => unreachable!("void has no field"),
+            DeclarationKind::Plain(spec) => {
+                let ty = self.spec(spec);
+                // a type that (indirectly) contains itself needs a heap allocation
+                let boxed = matches!(spec, TypeSpec::Named(target) if self.schema.contains_inline(target, owner));
+                if boxed { format!("Box<{}>", ty) } else { ty }
+            }
+            DeclarationKind::FixedArray(spec, size) => format!("[{}; {}]", self.spec(spec), self.size(size)?),
+            DeclarationKind::FixedOpaque(size) => format!("[u8; {}]", self.size(size)?),
+            DeclarationKind::VarOpaque(max) | DeclarationKind::String(max) => {
+                let max = match max {
+                    Some(max) => self.size(max)?,
+                    None => "{ i32::MAX }".to_string(),
+                };
+                format!("LimitedString<{}>", max)
+            }
+            DeclarationKind::VarArray(spec, Some(max)) => format!("VarArray<{}, {}>", self.spec(spec), self.size(max)?),
+            DeclarationKind::VarArray(spec, None) => format!("Vec<{}>", self.spec(spec)),
+            DeclarationKind::Optional(spec) => {
+                let inner = self.spec(spec);
+                let boxed = matches!(spec, TypeSpec::Named(target) if self.schema.contains_inline(target, owner));
+                if boxed { format!("Option<Box<{}>>", inner) } else { format!("Option<{}>", inner) }
+            }
+        };
+        Ok(Field { name, ty })
+    }
+
+    fn typedef(&self, name: &str, declaration: &Declaration) -> Result<String, CodegenError> {
+        let field = self.field(name, declaration)?;
+        Ok(format!("pub type {} = {};\n", name, field.ty))
+    }
+
+    fn enumeration(&self, name: &str, members: &[EnumMember]) -> Result<String, CodegenError> {
+        let mut output = String::new();
+        writeln!(output, "#[derive(Debug, Copy, Clone, Eq, PartialEq, XdrCodec)]").unwrap();
+        writeln!(output, "pub enum {} {{", name).unwrap();
+        for member in members {
+            writeln!(output, "    {} = {},", type_name(&member.name), self.schema.resolve_value(&member.value)?).unwrap();
+        }
+        writeln!(output, "}}").unwrap();
+        Ok(output)
+    }
+
+    fn structure(&self, name: &str, declarations: &[Declaration]) -> Result<String, CodegenError> {
+        let mut output = String::new();
+        writeln!(output, "#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]").unwrap();
+        writeln!(output, "pub struct {} {{", name).unwrap();
+        for declaration in declarations {
+            let field = self.field(name, declaration)?;
+            writeln!(output, "    pub {}: {},", field.name, field.ty).unwrap();
+        }
+        writeln!(output, "}}").unwrap();
+        Ok(output)
+    }
+
+    fn union(&self, name: &str, body: &UnionBody) -> Result<String, CodegenError> {
+        let discriminant = match self.schema.resolve_typedef(&body.discriminant.kind) {
+            DeclarationKind::Plain(TypeSpec::Int) => Discriminant::Int("i32"),
+            DeclarationKind::Plain(TypeSpec::UnsignedInt) => Discriminant::Int("u32"),
+            DeclarationKind::Plain(TypeSpec::Named(enum_name)) => match self.schema.get(enum_name) {
+                Some(Definition::Enum { members, .. }) => Discriminant::Enum(type_name(enum_name), members.clone()),
+                _ => return Err(CodegenError::UnknownName(enum_name.clone())),
+            },
+            _ => return Err(CodegenError::UnknownName(body.discriminant.name.clone())),
+        };
+
+        let mut output = String::new();
+        writeln!(output, "#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]").unwrap();
+        match &discriminant {
+            Discriminant::Int(ty) => writeln!(output, "#[xdr(switch = {})]", ty).unwrap(),
+            Discriminant::Enum(enum_name, _) => writeln!(output, "#[xdr(switch = {})]", enum_name).unwrap(),
+        }
+        writeln!(output, "pub enum {} {{", name).unwrap();
+        let mut covered = Vec::new();
+        for arm in &body.arms {
+            let payload = match arm.declaration.kind {
+                DeclarationKind::Void => None,
+                _ => Some(self.field(name, &arm.declaration)?),
+            };
+            for case in &arm.cases {
+                let variant = match (&discriminant, case) {
+                    (Discriminant::Enum(_, _), Value::Named(member)) => {
+                        covered.push(member.clone());
+                        type_name(member)
+                    }
+                    (Discriminant::Int(_), case) => {
+                        let value = self.schema.resolve_value(case)?;
+                        writeln!(output, "    #[xdr(case = {})]", value).unwrap();
+                        match &payload {
+                            Some(_) => type_name(&arm.declaration.name),
+                            None => format!("V{}", value),
+                        }
+                    }
+                    (Discriminant::Enum(enum_name, _), Value::Literal(value)) => {
+                        return Err(CodegenError::UnknownName(format!("{}({})", enum_name, value)))
+                    }
+                };
+                match &payload {
+                    Some(field) => writeln!(output, "    {}({}),", variant, field.ty).unwrap(),
+                    None => writeln!(output, "    {},", variant).unwrap(),
+                }
+            }
+        }
+        // `default: void` stands for every enum member that has no arm of its own
+        if let Some(default) = &body.default {
+            match (&discriminant, &default.kind) {
+                (Discriminant::Enum(_, members), DeclarationKind::Void) => {
+                    for member in members.iter().filter(|member| !covered.contains(&member.name)) {
+                        writeln!(output, "    {},", type_name(&member.name)).unwrap();
+                    }
+                }
+                _ => return Err(CodegenError::UnsupportedType { name: format!("{} default arm", name), line: body.line }),
+            }
+        }
+        writeln!(output, "}}").unwrap();
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn generate(source: &str) -> Result<String, CodegenError> {
+        let schema = Schema::new(parse(source)?)?;
+        Generator::new(&schema).types(None)
+    }
+
+    #[test]
+    fn union_with_default_void() {
+        let source = "
+            enum MessageType { HELLO = 13, PEERS = 5, GET_PEERS = 4 };
+            union Message switch (MessageType type) {
+            case HELLO:
+                unsigned int version;
+            default:
+                void;
+            };";
+        assert_eq!(generate(source).unwrap(), "\
+#[derive(Debug, Copy, Clone, Eq, PartialEq, XdrCodec)]
+pub enum MessageType {
+    Hello = 13,
+    Peers = 5,
+    GetPeers = 4,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]
+#[xdr(switch = MessageType)]
+pub enum Message {
+    Hello(u32),
+    Peers,
+    GetPeers,
+}
+");
+    }
+
+    #[test]
+    fn int_switched_union() {
+        let source = "
+            const V1 = 1;
+            union Ext switch (int v) {
+            case 0:
+                void;
+            case V1:
+                unsigned hyper value;
+            };";
+        assert_eq!(generate(source).unwrap(), "\
+#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]
+#[xdr(switch = i32)]
+pub enum Ext {
+    #[xdr(case = 0)]
+    V0,
+    #[xdr(case = 1)]
+    Value(u64),
+}
+");
+    }
+
+    #[test]
+    fn self_referencing_types_are_boxed() {
+        let source = "
+            struct Node { int value; Node* next; Node children<>; };
+            union Expr switch (int kind) {
+            case 0:
+                int literal;
+            case 1:
+                Sum sum;
+            };
+            struct Sum { Expr left; Expr right; };";
+        assert_eq!(generate(source).unwrap(), "\
+#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]
+pub struct Node {
+    pub value: i32,
+    pub next: Option<Box<Node>>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]
+#[xdr(switch = i32)]
+pub enum Expr {
+    #[xdr(case = 0)]
+    Literal(i32),
+    #[xdr(case = 1)]
+    Sum(Box<Sum>),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]
+pub struct Sum {
+    pub left: Box<Expr>,
+    pub right: Box<Expr>,
+}
+");
+    }
+
+    #[test]
+    fn default_arm_with_a_value_is_unsupported() {
+        let source = "
+            union Ext switch (int v) {
+            case 0:
+                void;
+            default:
+                int other;
+            };";
+        assert!(matches!(generate(source), Err(CodegenError::UnsupportedType { line: 2, .. })));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::errors::CodegenError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Ident(String),
+    Number(i64),
+    Punct(char),
+}
+
+#[derive(Debug, Clone)]
+pub struct Spanned {
+    pub token: Token,
+    pub line: usize,
+}
+
+/// Splits an XDR source into tokens, dropping comments and `%` pass-through lines.
+pub fn tokenize(source: &str) -> Result<Vec<Spanned>, CodegenError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut index = 0;
+    let mut at_line_start = true;
+    while index < chars.len() {
+        let c = chars[index];
+        match c {
+            '\n' => {
+                line += 1;
+                index += 1;
+                at_line_start = true;
+                continue;
+            }
+            c if c.is_whitespace() => {
+                index += 1;
+                continue;
+            }
+            '%' if at_line_start => {
+                while index < chars.len() && chars[index] != '\n' {
+                    index += 1;
+                }
+                continue;
+            }
+            '/' if chars.get(index + 1) == Some(&'/') => {
+                while index < chars.len() && chars[index] != '\n' {
+                    index += 1;
+                }
+                continue;
+            }
+            '/' if chars.get(index + 1) == Some(&'*') => {
+                index += 2;
+                while index < chars.len() && !(chars[index] == '*' && chars.get(index + 1) == Some(&'/')) {
+                    if chars[index] == '\n' {
+                        line += 1;
+                    }
+                    index += 1;
+                }
+                index += 2;
+                continue;
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let start = index;
+                while index < chars.len() && (chars[index].is_ascii_alphanumeric() || chars[index] == '_') {
+                    index += 1;
+                }
+                tokens.push(Spanned { token: Token::Ident(chars[start..index].iter().collect()), line });
+            }
+            c if c.is_ascii_digit() || (c == '-' && chars.get(index + 1).is_some_and(|c| c.is_ascii_digit())) => {
+                let start = index;
+                index += 1;
+                while index < chars.len() && chars[index].is_ascii_alphanumeric() {
+                    index += 1;
+                }
+                let literal: String = chars[start..index].iter().collect();
+                tokens.push(Spanned { token: Token::Number(parse_number(&literal, line)?), line });
+            }
+            '{' | '}' | '(' | ')' | '[' | ']' | '<' | '>' | ';' | ':' | ',' | '=' | '*' => {
+                tokens.push(Spanned { token: Token::Punct(c), line });
+                index += 1;
+            }
+            other => return Err(CodegenError::UnexpectedCharacter { character: other, line }),
+        }
+        at_line_start = false;
+    }
+    Ok(tokens)
+}
+
+fn parse_number(literal: &str, line: usize) -> Result<i64, CodegenError> {
+    let (negative, digits) = match literal.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, literal),
+    };
+    let value = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => i64::from_str_radix(hex, 16),
+        None => digits.parse::<i64>(),
+    }
+    .map_err(|_| CodegenError::InvalidNumber { literal: literal.to_string(), line })?;
+    Ok(if negative { -value } else { value })
+}
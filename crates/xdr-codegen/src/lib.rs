@@ -0,0 +1,36 @@
+mod ast;
+pub mod errors;
+mod generator;
+mod lexer;
+mod naming;
+mod parser;
+mod schema;
+
+use std::fs;
+use std::path::Path;
+
+pub use errors::CodegenError;
+use generator::Generator;
+use schema::Schema;
+
+/// Rust source generated from a set of `.x` files.
+pub struct Generated {
+    /// Types deriving `XdrCodec`, expecting `LimitedString`, `VarArray`, `XdrCodec` and the constants in scope.
+    pub types: String,
+    pub constants: String,
+}
+
+/// Parses the given XDR files, which may refer to each other's definitions, and generates Rust
+/// code in file order for every type reachable from `roots`, or for all types if `roots` is empty.
+pub fn generate<P: AsRef<Path>>(files: &[P], roots: &[&str]) -> Result<Generated, CodegenError> {
+    let mut definitions = Vec::new();
+    for path in files {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|source| CodegenError::Io { path: path.display().to_string(), source })?;
+        definitions.extend(parser::parse(&source)?);
+    }
+    let schema = Schema::new(definitions)?;
+    let generator = Generator::new(&schema);
+    let names = (!roots.is_empty()).then(|| schema.reachable(roots));
+    Ok(Generated { types: generator.types(names.as_deref())?, constants: generator.constants() })
+}
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::{env, fs};
+
+const USAGE: &str = "usage: xdr-codegen [--root <type>]... <output dir> <file.x>...";
+
+fn main() -> ExitCode {
+    let mut roots = Vec::new();
+    let mut positional = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--root" => match args.next() {
+                Some(root) => roots.push(root),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => positional.push(arg),
+        }
+    }
+    if positional.len() < 2 {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    }
+
+    let output_dir = PathBuf::from(&positional[0]);
+    let roots: Vec<&str> = roots.iter().map(String::as_str).collect();
+    let generated = match xdr_codegen::generate(&positional[1..], &roots) {
+        Ok(generated) => generated,
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+    for (name, contents) in [("types.rs", generated.types), ("constants.rs", generated.constants)] {
+        let path = output_dir.join(name);
+        if let Err(error) = fs::write(&path, contents) {
+            eprintln!("Failed to write {}: {}", path.display(), error);
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
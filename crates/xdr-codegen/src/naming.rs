@@ -0,0 +1,52 @@
+/// Splits an XDR identifier (`peerID`, `SCPQuorumSet`, `ENVELOPE_TYPE_TX_V0`) into words.
+fn words(identifier: &str) -> Vec<String> {
+    let chars: Vec<char> = identifier.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (index, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_ascii_uppercase() && !current.is_empty() {
+            let previous = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_ascii_lowercase());
+            if previous.is_ascii_lowercase() || previous.is_ascii_digit() || (previous.is_ascii_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// `SCPQuorumSet` -> `ScpQuorumSet`, `ERROR_MSG` -> `ErrorMsg`, `uint256` -> `Uint256`.
+pub fn type_name(identifier: &str) -> String {
+    words(identifier)
+        .iter()
+        .map(|word| {
+            let lower = word.to_ascii_lowercase();
+            let mut chars = lower.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// `peerID` -> `peer_id`, `type` -> `type_`.
+pub fn field_name(identifier: &str) -> String {
+    let name = words(identifier).iter().map(|word| word.to_ascii_lowercase()).collect::<Vec<_>>().join("_");
+    match name.as_str() {
+        "type" | "fn" | "ref" | "match" | "mod" | "struct" | "enum" | "union" | "impl" | "use" | "self" | "in" | "loop" => {
+            format!("{}_", name)
+        }
+        _ => name,
+    }
+}
@@ -0,0 +1,263 @@
+use crate::ast::*;
+use crate::errors::CodegenError;
+use crate::lexer::{tokenize, Spanned, Token};
+
+pub fn parse(source: &str) -> Result<Vec<Definition>, CodegenError> {
+    let mut parser = Parser { tokens: tokenize(source)?, position: 0 };
+    let mut definitions = Vec::new();
+    while !parser.at_end() {
+        if parser.eat_keyword("namespace") {
+            parser.expect_ident()?;
+            parser.expect('{')?;
+            while !parser.eat('}') {
+                definitions.push(parser.definition()?);
+            }
+        } else {
+            definitions.push(parser.definition()?);
+        }
+    }
+    Ok(definitions)
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    position: usize,
+}
+
+impl Parser {
+    fn at_end(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|spanned| &spanned.token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens.get(self.position).or(self.tokens.last()).map_or(0, |spanned| spanned.line)
+    }
+
+    fn unexpected(&self, expected: &str) -> CodegenError {
+        CodegenError::UnexpectedToken {
+            expected: expected.to_string(),
+            found: self.peek().map_or("end of file".to_string(), |token| format!("{:?}", token)),
+            line: self.line(),
+        }
+    }
+
+    fn eat(&mut self, punct: char) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: char) -> Result<(), CodegenError> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", punct)))
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(ident)) if ident == keyword => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), CodegenError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.unexpected(keyword))
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, CodegenError> {
+        match self.peek().cloned() {
+            Some(Token::Ident(ident)) => {
+                self.position += 1;
+                Ok(ident)
+            }
+            _ => Err(self.unexpected("identifier")),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, CodegenError> {
+        match self.peek().cloned() {
+            Some(Token::Number(number)) => {
+                self.position += 1;
+                Ok(Value::Literal(number))
+            }
+            Some(Token::Ident(ident)) => {
+                self.position += 1;
+                Ok(Value::Named(ident))
+            }
+            _ => Err(self.unexpected("constant")),
+        }
+    }
+
+    fn definition(&mut self) -> Result<Definition, CodegenError> {
+        let definition = match self.expect_ident()?.as_str() {
+            "const" => {
+                let name = self.expect_ident()?;
+                self.expect('=')?;
+                match self.value()? {
+                    Value::Literal(value) => Definition::Const { name, value },
+                    Value::Named(_) => return Err(self.unexpected("numeric constant")),
+                }
+            }
+            "typedef" => Definition::Typedef(self.declaration()?),
+            "enum" => {
+                let name = self.expect_ident()?;
+                Definition::Enum { name, members: self.enum_body()? }
+            }
+            "struct" => {
+                let name = self.expect_ident()?;
+                Definition::Struct { name, fields: self.struct_body()? }
+            }
+            "union" => {
+                let name = self.expect_ident()?;
+                Definition::Union { name, body: self.union_body()? }
+            }
+            _ => {
+                self.position -= 1;
+                return Err(self.unexpected("definition"));
+            }
+        };
+        self.expect(';')?;
+        Ok(definition)
+    }
+
+    fn enum_body(&mut self) -> Result<Vec<EnumMember>, CodegenError> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+        loop {
+            let name = self.expect_ident()?;
+            self.expect('=')?;
+            members.push(EnumMember { name, value: self.value()? });
+            if !self.eat(',') {
+                break;
+            }
+        }
+        self.expect('}')?;
+        Ok(members)
+    }
+
+    fn struct_body(&mut self) -> Result<Vec<Declaration>, CodegenError> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        while !self.eat('}') {
+            fields.push(self.declaration()?);
+            self.expect(';')?;
+        }
+        Ok(fields)
+    }
+
+    fn union_body(&mut self) -> Result<UnionBody, CodegenError> {
+        let line = self.line();
+        self.expect_keyword("switch")?;
+        self.expect('(')?;
+        let discriminant = self.declaration()?;
+        self.expect(')')?;
+        self.expect('{')?;
+        let mut arms = Vec::new();
+        let mut default = None;
+        while !self.eat('}') {
+            if self.eat_keyword("default") {
+                self.expect(':')?;
+                default = Some(self.declaration()?);
+                self.expect(';')?;
+                continue;
+            }
+            let mut cases = Vec::new();
+            while self.eat_keyword("case") {
+                cases.push(self.value()?);
+                self.expect(':')?;
+            }
+            if cases.is_empty() {
+                return Err(self.unexpected("case"));
+            }
+            let declaration = self.declaration()?;
+            self.expect(';')?;
+            arms.push(UnionArm { cases, declaration });
+        }
+        Ok(UnionBody { discriminant, arms, default, line })
+    }
+
+    fn declaration(&mut self) -> Result<Declaration, CodegenError> {
+        if self.eat_keyword("void") {
+            return Ok(Declaration { name: String::new(), kind: DeclarationKind::Void });
+        }
+        if self.eat_keyword("opaque") {
+            let name = self.expect_ident()?;
+            let kind = if self.eat('[') {
+                let size = self.value()?;
+                self.expect(']')?;
+                DeclarationKind::FixedOpaque(size)
+            } else {
+                DeclarationKind::VarOpaque(self.var_size()?)
+            };
+            return Ok(Declaration { name, kind });
+        }
+        if self.eat_keyword("string") {
+            let name = self.expect_ident()?;
+            return Ok(Declaration { name, kind: DeclarationKind::String(self.var_size()?) });
+        }
+        let spec = self.type_spec()?;
+        if self.eat('*') {
+            let name = self.expect_ident()?;
+            return Ok(Declaration { name, kind: DeclarationKind::Optional(spec) });
+        }
+        let name = self.expect_ident()?;
+        let kind = if self.eat('[') {
+            let size = self.value()?;
+            self.expect(']')?;
+            DeclarationKind::FixedArray(spec, size)
+        } else if self.peek() == Some(&Token::Punct('<')) {
+            DeclarationKind::VarArray(spec, self.var_size()?)
+        } else {
+            DeclarationKind::Plain(spec)
+        };
+        Ok(Declaration { name, kind })
+    }
+
+    fn var_size(&mut self) -> Result<Option<Value>, CodegenError> {
+        self.expect('<')?;
+        if self.eat('>') {
+            return Ok(None);
+        }
+        let size = self.value()?;
+        self.expect('>')?;
+        Ok(Some(size))
+    }
+
+    fn type_spec(&mut self) -> Result<TypeSpec, CodegenError> {
+        let ident = self.expect_ident()?;
+        Ok(match ident.as_str() {
+            "int" => TypeSpec::Int,
+            "hyper" => TypeSpec::Hyper,
+            "bool" => TypeSpec::Bool,
+            "unsigned" => {
+                if self.eat_keyword("hyper") {
+                    TypeSpec::UnsignedHyper
+                } else {
+                    self.eat_keyword("int");
+                    TypeSpec::UnsignedInt
+                }
+            }
+            "enum" => TypeSpec::Enum(self.enum_body()?),
+            "struct" => TypeSpec::Struct(self.struct_body()?),
+            "union" => TypeSpec::Union(Box::new(self.union_body()?)),
+            "float" | "double" | "quadruple" => return Err(CodegenError::UnsupportedType { name: ident, line: self.line() }),
+            _ => TypeSpec::Named(ident),
+        })
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::*;
+use crate::errors::CodegenError;
+use crate::naming::type_name;
+
+/// Flattened view over all parsed files: anonymous nested structs and unions are lifted into
+/// named definitions, and every definition can be looked up by its Rust name.
+pub struct Schema {
+    pub definitions: Vec<Definition>,
+    index: HashMap<String, usize>,
+    constants: HashMap<String, i64>,
+    enum_members: HashMap<String, (String, i64)>,
+}
+
+impl Schema {
+    pub fn new(parsed: Vec<Definition>) -> Result<Self, CodegenError> {
+        let mut definitions = Vec::new();
+        for definition in parsed {
+            flatten(definition, &mut definitions);
+        }
+        let mut schema = Schema { definitions, index: HashMap::new(), constants: HashMap::new(), enum_members: HashMap::new() };
+        for (position, definition) in schema.definitions.iter().enumerate() {
+            schema.index.insert(type_name(definition.name()), position);
+            if let Definition::Const { name, value } = definition {
+                schema.constants.insert(name.clone(), *value);
+            }
+        }
+        let enums: Vec<(String, Vec<EnumMember>)> = schema
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Enum { name, members } => Some((name.clone(), members.clone())),
+                _ => None,
+            })
+            .collect();
+        for (name, members) in enums {
+            for member in members {
+                let value = schema.resolve_value(&member.value)?;
+                schema.enum_members.insert(member.name.clone(), (type_name(&name), value));
+            }
+        }
+        Ok(schema)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Definition> {
+        self.index.get(&type_name(name)).map(|position| &self.definitions[*position])
+    }
+
+    pub fn resolve_value(&self, value: &Value) -> Result<i64, CodegenError> {
+        match value {
+            Value::Literal(literal) => Ok(*literal),
+            Value::Named(name) => self
+                .constants
+                .get(name)
+                .or_else(|| self.enum_members.get(name).map(|(_, value)| value))
+                .copied()
+                .ok_or_else(|| CodegenError::UnknownName(name.clone())),
+        }
+    }
+
+    pub fn is_constant(&self, name: &str) -> bool {
+        self.constants.contains_key(name)
+    }
+
+    /// Follows typedefs until a declaration that is not a plain alias of another named type.
+    pub fn resolve_typedef<'a>(&'a self, kind: &'a DeclarationKind) -> &'a DeclarationKind {
+        let mut current = kind;
+        while let DeclarationKind::Plain(TypeSpec::Named(name)) = current {
+            match self.get(name) {
+                Some(Definition::Typedef(declaration)) => current = &declaration.kind,
+                _ => break,
+            }
+        }
+        current
+    }
+
+    /// Whether a value of `from` contains a value of `target` without going through a heap allocation.
+    pub fn contains_inline(&self, from: &str, target: &str) -> bool {
+        let mut visited = HashSet::new();
+        self.contains_inline_inner(from, target, &mut visited)
+    }
+
+    fn contains_inline_inner(&self, from: &str, target: &str, visited: &mut HashSet<String>) -> bool {
+        if type_name(from) == type_name(target) {
+            return true;
+        }
+        if !visited.insert(type_name(from)) {
+            return false;
+        }
+        let declarations: Vec<&Declaration> = match self.get(from) {
+            Some(Definition::Struct { fields, .. }) => fields.iter().collect(),
+            Some(Definition::Union { body, .. }) => body.arms.iter().map(|arm| &arm.declaration).collect(),
+            Some(Definition::Typedef(declaration)) => vec![declaration],
+            _ => vec![],
+        };
+        declarations.into_iter().any(|declaration| match &declaration.kind {
+            DeclarationKind::Plain(TypeSpec::Named(name))
+            | DeclarationKind::FixedArray(TypeSpec::Named(name), _)
+            | DeclarationKind::Optional(TypeSpec::Named(name)) => self.contains_inline_inner(name, target, visited),
+            _ => false,
+        })
+    }
+
+    /// Names of all definitions reachable from `roots`, in schema order.
+    pub fn reachable(&self, roots: &[&str]) -> Vec<String> {
+        let mut visited = HashSet::new();
+        let mut stack: Vec<String> = roots.iter().map(|root| type_name(root)).collect();
+        while let Some(name) = stack.pop() {
+            if !visited.insert(name.clone()) {
+                continue;
+            }
+            let Some(definition) = self.get(&name) else { continue };
+            let mut visit = |declaration: &Declaration| {
+                let spec = match &declaration.kind {
+                    DeclarationKind::Plain(spec)
+                    | DeclarationKind::FixedArray(spec, _)
+                    | DeclarationKind::VarArray(spec, _)
+                    | DeclarationKind::Optional(spec) => Some(spec),
+                    _ => None,
+                };
+                if let Some(TypeSpec::Named(name)) = spec {
+                    stack.push(type_name(name));
+                }
+            };
+            match definition {
+                Definition::Struct { fields, .. } => fields.iter().for_each(&mut visit),
+                Definition::Union { body, .. } => {
+                    visit(&body.discriminant);
+                    body.arms.iter().for_each(|arm| visit(&arm.declaration));
+                    body.default.iter().for_each(&mut visit);
+                }
+                Definition::Typedef(declaration) => visit(declaration),
+                _ => {}
+            }
+        }
+        self.definitions
+            .iter()
+            .map(|definition| type_name(definition.name()))
+            .filter(|name| visited.contains(name))
+            .collect()
+    }
+}
+
+fn flatten(definition: Definition, output: &mut Vec<Definition>) {
+    match definition {
+        Definition::Struct { name, fields } => {
+            let prefix = type_name(&name);
+            let mut nested = Vec::new();
+            let fields = fields.into_iter().map(|field| lift(field, &prefix, &mut nested)).collect();
+            output.push(Definition::Struct { name, fields });
+            output.extend(nested);
+        }
+        Definition::Union { name, body } => {
+            let prefix = type_name(&name);
+            let mut nested = Vec::new();
+            let body = lift_union_arms(body, &prefix, &mut nested);
+            output.push(Definition::Union { name, body });
+            output.extend(nested);
+        }
+        other => output.push(other),
+    }
+}
+
+/// Nested definitions are named after the closest enclosing struct (or top-level union) and the
+/// field they appear in, e.g. `ScpStatement.pledges.prepare` becomes `ScpStatementPrepare`.
+fn lift(declaration: Declaration, struct_prefix: &str, nested: &mut Vec<Definition>) -> Declaration {
+    let name = format!("{}{}", struct_prefix, type_name(&declaration.name));
+    let kind = match declaration.kind {
+        DeclarationKind::Plain(TypeSpec::Struct(fields)) => {
+            let mut inner = Vec::new();
+            let fields = fields.into_iter().map(|field| lift(field, &name, &mut inner)).collect();
+            nested.push(Definition::Struct { name: name.clone(), fields });
+            nested.extend(inner);
+            DeclarationKind::Plain(TypeSpec::Named(name))
+        }
+        DeclarationKind::Plain(TypeSpec::Union(body)) => {
+            let mut inner = Vec::new();
+            let body = lift_union_arms(*body, struct_prefix, &mut inner);
+            nested.push(Definition::Union { name: name.clone(), body });
+            nested.extend(inner);
+            DeclarationKind::Plain(TypeSpec::Named(name))
+        }
+        DeclarationKind::Plain(TypeSpec::Enum(members)) => {
+            nested.push(Definition::Enum { name: name.clone(), members });
+            DeclarationKind::Plain(TypeSpec::Named(name))
+        }
+        other => other,
+    };
+    Declaration { name: declaration.name, kind }
+}
+
+fn lift_union_arms(body: UnionBody, prefix: &str, nested: &mut Vec<Definition>) -> UnionBody {
+    UnionBody {
+        discriminant: body.discriminant,
+        arms: body
+            .arms
+            .into_iter()
+            .map(|arm| UnionArm { cases: arm.cases, declaration: lift(arm.declaration, prefix, nested) })
+            .collect(),
+        default: body.default.map(|declaration| lift(declaration, prefix, nested)),
+        line: body.line,
+    }
+}
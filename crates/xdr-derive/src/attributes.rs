@@ -0,0 +1,31 @@
+use syn::{Attribute, Expr, Result, Type};
+
+pub fn switch(attributes: &[Attribute]) -> Result<Option<Type>> {
+    let mut result = None;
+    for attribute in attributes.iter().filter(|attribute| attribute.path().is_ident("xdr")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("switch") {
+                result = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `switch = ...`"))
+            }
+        })?;
+    }
+    Ok(result)
+}
+
+pub fn case(attributes: &[Attribute]) -> Result<Option<Expr>> {
+    let mut result = None;
+    for attribute in attributes.iter().filter(|attribute| attribute.path().is_ident("xdr")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("case") {
+                result = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `case = ...`"))
+            }
+        })?;
+    }
+    Ok(result)
+}
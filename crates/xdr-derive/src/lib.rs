@@ -0,0 +1,131 @@
+mod attributes;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, Result, Type};
+
+/// Derives `xdr::XdrCodec` by encoding the XDR representation of the item:
+///
+/// * structs encode their fields in declaration order;
+/// * enums with only unit variants are XDR enums and encode their discriminant as an `int`;
+/// * enums annotated with `#[xdr(switch = Type)]` are XDR discriminated unions. Every variant
+///   holds at most one value and is selected by `Type::Variant`, or by `#[xdr(case = ...)]`
+///   when the switch is an integer.
+///
+/// Decoding goes one level deeper in the `ReadStream`, which bounds how deeply values nest.
+#[proc_macro_derive(XdrCodec, attributes(xdr))]
+pub fn derive_xdr_codec(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let (encode, decode) = match &input.data {
+        Data::Struct(data) => expand_struct(data)?,
+        Data::Enum(data) => match attributes::switch(&input.attrs)? {
+            Some(switch) => expand_union(data, &switch)?,
+            None => expand_enum(data)?,
+        },
+        Data::Union(_) => return Err(Error::new(input.span(), "XdrCodec cannot be derived for Rust unions")),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::xdr::XdrCodec for #name #type_generics #where_clause {
+            fn encode(&self, write_stream: &mut ::xdr::streams::WriteStream) {
+                #encode
+            }
+
+            #[allow(unreachable_patterns)]
+            fn from_xdr_buffered<XdrSource: AsRef<[u8]>>(
+                read_stream: &mut ::xdr::streams::ReadStream<XdrSource>,
+            ) -> Result<Self, ::xdr::streams::DecodeError> {
+                read_stream.nested(|read_stream| { #decode })
+            }
+        }
+    })
+}
+
+fn decode_value(ty: &Type) -> TokenStream2 {
+    quote! { <#ty as ::xdr::XdrCodec>::from_xdr_buffered(read_stream)? }
+}
+
+fn expand_struct(data: &DataStruct) -> Result<(TokenStream2, TokenStream2)> {
+    let mut encode = Vec::new();
+    let mut decode = Vec::new();
+    for (index, field) in data.fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => quote! { #ident },
+            None => {
+                let index = syn::Index::from(index);
+                quote! { #index }
+            }
+        };
+        encode.push(quote! { ::xdr::XdrCodec::encode(&self.#member, write_stream); });
+        let value = decode_value(&field.ty);
+        decode.push(quote! { #member: #value });
+    }
+    Ok((quote! { #(#encode)* }, quote! { Ok(Self { #(#decode),* }) }))
+}
+
+fn expand_enum(data: &DataEnum) -> Result<(TokenStream2, TokenStream2)> {
+    let mut encode = Vec::new();
+    let mut decode = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(variant.span(), "enums with values need #[xdr(switch = ...)]"));
+        }
+        let ident = &variant.ident;
+        encode.push(quote! { Self::#ident => Self::#ident as i32 });
+        decode.push(quote! { value if value == Self::#ident as i32 => Ok(Self::#ident) });
+    }
+    let encode = quote! {
+        let value: i32 = match self { #(#encode),* };
+        ::xdr::XdrCodec::encode(&value, write_stream);
+    };
+    let decode = quote! {
+        match <i32 as ::xdr::XdrCodec>::from_xdr_buffered(read_stream)? {
+            #(#decode,)*
+            _ => Err(::xdr::streams::DecodeError::InvalidEnumDiscriminator { at_position: read_stream.get_position() }),
+        }
+    };
+    Ok((encode, decode))
+}
+
+fn expand_union(data: &DataEnum, switch: &Type) -> Result<(TokenStream2, TokenStream2)> {
+    let mut encode = Vec::new();
+    let mut decode = Vec::new();
+    for variant in &data.variants {
+        let ident = &variant.ident;
+        let case = match attributes::case(&variant.attrs)? {
+            Some(case) => quote! { #case },
+            None => quote! { #switch::#ident },
+        };
+        let encode_discriminant = quote! { <#switch as ::xdr::XdrCodec>::encode(&#case, write_stream); };
+        match &variant.fields {
+            Fields::Unit => {
+                encode.push(quote! { Self::#ident => { #encode_discriminant } });
+                decode.push(quote! { #case => Ok(Self::#ident) });
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let decode_value = decode_value(&fields.unnamed[0].ty);
+                encode.push(quote! { Self::#ident(value) => { #encode_discriminant ::xdr::XdrCodec::encode(value, write_stream); } });
+                decode.push(quote! { #case => Ok(Self::#ident(#decode_value)) });
+            }
+            _ => return Err(Error::new(variant.span(), "union arms hold at most one unnamed value")),
+        }
+    }
+    let encode = quote! {
+        match self { #(#encode)* }
+    };
+    let decode = quote! {
+        match <#switch as ::xdr::XdrCodec>::from_xdr_buffered(read_stream)? {
+            #(#decode,)*
+            _ => Err(::xdr::streams::DecodeError::InvalidEnumDiscriminator { at_position: read_stream.get_position() }),
+        }
+    };
+    Ok((encode, decode))
+}
@@ -0,0 +1,80 @@
+use std::fmt::Debug;
+use xdr::{DecodeError, XdrCodec};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, XdrCodec)]
+enum MessageType {
+    Hello = 13,
+    Peers = 5,
+    GetPeers = 4,
+}
+
+/// `default: void` for `Peers` and `GetPeers`.
+#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]
+#[xdr(switch = MessageType)]
+enum Message {
+    Hello(u32),
+    Peers,
+    GetPeers,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]
+#[xdr(switch = i32)]
+enum Ext {
+    #[xdr(case = 0)]
+    V0,
+    #[xdr(case = 1)]
+    Value(u64),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]
+struct Node {
+    value: i32,
+    next: Option<Box<Node>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]
+#[xdr(switch = i32)]
+enum Expr {
+    #[xdr(case = 0)]
+    Literal(i32),
+    #[xdr(case = 1)]
+    Sum(Box<Sum>),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, XdrCodec)]
+struct Sum {
+    left: Box<Expr>,
+    right: Box<Expr>,
+}
+
+fn assert_round_trip<T: XdrCodec + PartialEq + Debug>(value: T, xdr: &[u8]) {
+    assert_eq!(value.to_xdr(), xdr);
+    assert_eq!(T::decoded(xdr).unwrap(), (value, xdr.len()));
+}
+
+#[test]
+fn enum_switched_union() {
+    assert_round_trip(Message::Hello(7), &[0, 0, 0, 13, 0, 0, 0, 7]);
+    assert_round_trip(Message::Peers, &[0, 0, 0, 5]);
+    assert_round_trip(Message::GetPeers, &[0, 0, 0, 4]);
+    assert!(matches!(Message::decoded([0, 0, 0, 6]), Err(DecodeError::InvalidEnumDiscriminator { at_position: 4 })));
+}
+
+#[test]
+fn int_switched_union() {
+    assert_round_trip(Ext::V0, &[0, 0, 0, 0]);
+    assert_round_trip(Ext::Value(1 << 32), &[0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0]);
+    assert!(matches!(Ext::decoded([0, 0, 0, 2]), Err(DecodeError::InvalidEnumDiscriminator { .. })));
+}
+
+#[test]
+fn self_referencing_struct() {
+    let list = Node { value: 1, next: Some(Box::new(Node { value: 2, next: None })) };
+    assert_round_trip(list, &[0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 0]);
+}
+
+#[test]
+fn mutually_recursive_union() {
+    let sum = Expr::Sum(Box::new(Sum { left: Box::new(Expr::Literal(3)), right: Box::new(Expr::Literal(-1)) }));
+    assert_round_trip(sum, &[0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+}
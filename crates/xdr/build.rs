@@ -0,0 +1,31 @@
+use std::path::Path;
+use std::{env, fs};
+
+// Order of the generated definitions; a file may refer to definitions of any other file.
+const SCHEMA_FILES: &[&str] = &[
+    "Stellar-types.x",
+    "Stellar-SCP.x",
+    "Stellar-contract.x",
+    "Stellar-contract-config-setting.x",
+    "Stellar-contract-env-meta.x",
+    "Stellar-contract-meta.x",
+    "Stellar-contract-spec.x",
+    "Stellar-ledger-entries.x",
+    "Stellar-transaction.x",
+    "Stellar-ledger.x",
+    "Stellar-overlay.x",
+    "Stellar-internal.x",
+];
+
+// Types generated together with everything they refer to.
+const ROOT_TYPES: &[&str] = &["AuthenticatedMessage"];
+
+fn main() {
+    println!("cargo:rerun-if-changed=xdr");
+    let files: Vec<_> = SCHEMA_FILES.iter().map(|name| Path::new("xdr").join(name)).collect();
+    let generated = xdr_codegen::generate(&files, ROOT_TYPES).unwrap_or_else(|error| panic!("Failed to generate XDR types: {}", error));
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("types.rs"), generated.types).unwrap();
+    fs::write(Path::new(&out_dir).join("constants.rs"), generated.constants).unwrap();
+}
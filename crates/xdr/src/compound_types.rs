@@ -1,5 +1,5 @@
 use crate::streams::{DecodeError, ReadStream, WriteStream};
-use crate::xdr_codec::XdrCodec;
+use crate::xdr_codec::{read_var_array, XdrCodec};
 
 #[derive(Debug)]
 pub struct XdrArchive<T>(Vec<T>);
@@ -13,28 +13,106 @@ impl<T: XdrCodec> XdrArchive<T> {
     }
 }
 
+/// Set in a record marking header if the fragment is the last one of its record (RFC 5531, section 11).
+const LAST_FRAGMENT: u32 = 0x80_00_00_00;
+/// Largest fragment a record marking header can describe.
+pub const MAX_FRAGMENT_LENGTH: usize = 0x7f_ff_ff_ff;
+/// Largest record a record marking header can describe.
+pub const MAX_RECORD_LENGTH: usize = 0x7f_ff_ff_ff;
+/// Most fragments a record may be split into. stellar-core sends every record as one fragment.
+pub const MAX_FRAGMENTS: usize = 1024;
+
+/// Reads the header of the `index`th fragment of a record, which is rejected if it has too many
+/// fragments, an empty fragment before the last or, once its headers say so, more than
+/// `max_length` bytes. Returns the length of the fragment and whether it is the last.
+fn read_fragment_header<R: AsRef<[u8]>>(
+    read_stream: &mut ReadStream<R>,
+    index: usize,
+    record_length: usize,
+    max_length: usize,
+) -> Result<(usize, bool), DecodeError> {
+    let position = read_stream.get_position();
+    let header = read_stream.read_next_u32()?;
+    let fragment_length = (header & !LAST_FRAGMENT) as usize;
+    let last = header & LAST_FRAGMENT != 0;
+    if index == MAX_FRAGMENTS {
+        return Err(DecodeError::TooManyFragments { at_position: position, allowed_fragments: MAX_FRAGMENTS });
+    }
+    if fragment_length == 0 && !last {
+        return Err(DecodeError::EmptyFragment { at_position: position });
+    }
+    if record_length + fragment_length > max_length {
+        return Err(DecodeError::RecordTooLarge { requested_length: record_length + fragment_length, allowed_length: max_length });
+    }
+    Ok((fragment_length, last))
+}
+
+/// Returns the number of bytes the first record of `buf` takes including its record marking
+/// headers, or `None` if the record is not complete yet. Records longer than `max_length` are
+/// rejected as soon as their headers say so.
+pub fn record_length(buf: &[u8], max_length: usize) -> Result<Option<usize>, DecodeError> {
+    let mut read_stream = ReadStream::new(buf);
+    let mut record_length = 0;
+    for index in 0.. {
+        if read_stream.no_of_bytes_left_to_read() < 4 {
+            return Ok(None);
+        }
+        let (fragment_length, last) = read_fragment_header(&mut read_stream, index, record_length, max_length)?;
+        record_length += fragment_length;
+        if read_stream.read_raw_bytes(fragment_length).is_err() {
+            return Ok(None);
+        }
+        if last {
+            return Ok(Some(read_stream.get_position()));
+        }
+    }
+    unreachable!()
+}
+
+/// Reads the next record, joining its fragments; records longer than `max_length` are rejected.
+pub fn read_record<R: AsRef<[u8]>>(read_stream: &mut ReadStream<R>, max_length: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut record = Vec::new();
+    for index in 0.. {
+        let (fragment_length, last) = read_fragment_header(read_stream, index, record.len(), max_length)?;
+        record.extend_from_slice(read_stream.read_raw_bytes(fragment_length)?);
+        if last {
+            return Ok(record);
+        }
+    }
+    unreachable!()
+}
+
+/// Writes `record` as one or more fragments.
+pub fn write_record(write_stream: &mut WriteStream, record: &[u8]) {
+    let mut fragments = record.chunks(MAX_FRAGMENT_LENGTH).peekable();
+    if fragments.peek().is_none() {
+        write_stream.write_u32(LAST_FRAGMENT);
+    }
+    while let Some(fragment) = fragments.next() {
+        let last = if fragments.peek().is_none() { LAST_FRAGMENT } else { 0 };
+        write_stream.write_u32(fragment.len() as u32 | last);
+        write_stream.write_raw_bytes(fragment);
+    }
+}
+
 impl <T: XdrCodec> XdrCodec for XdrArchive<T> {
     fn encode(&self, write_stream: &mut WriteStream) {
         for item in self.0.iter() {
-            let item_xdr = item.to_xdr();
-            let length = item_xdr.len();
-            if length < 0x80_00_00_00 {
-                write_stream.write_u32((length as u32) | 0x80_00_00_00);
-                write_stream.write_binary_data(&item_xdr);
-            }
+            write_record(write_stream, &item.to_xdr());
         }
     }
 
     fn from_xdr_buffered<R: AsRef<[u8]>>(read_stream: &mut ReadStream<R>) -> Result<Self, DecodeError> {
         let mut result = Vec::<T>::new();
         while read_stream.no_of_bytes_left_to_read() > 0 {
-            let length = read_stream.read_next_u32()? & 0x7f_ff_ff_ff;
-            let old_position = read_stream.get_position();
+            let position = read_stream.get_position();
+            let record = read_record(read_stream, MAX_RECORD_LENGTH)?;
+            let mut record_stream = ReadStream::new(&record);
 
-            result.push(T::from_xdr_buffered(read_stream)?);
+            result.push(T::from_xdr_buffered(&mut record_stream)?);
 
-            if read_stream.get_position() - old_position != length as usize {
-                return Err(DecodeError::InvalidXdrArchiveLength { at_position: old_position });
+            if record_stream.no_of_bytes_left_to_read() != 0 {
+                return Err(DecodeError::InvalidXdrArchiveLength { at_position: position });
             }
         }
 
@@ -83,3 +161,168 @@ impl <const N: i32>  XdrCodec for LimitedString<N> {
         LimitedString::new(read_stream.read_bytes_array(length as usize)?)
     }
 }
+
+/// XDR variable-length array `T<N>`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VarArray<T, const N: i32>(Vec<T>);
+impl<T, const N: i32> VarArray<T, N> {
+    pub fn new(vec: Vec<T>) -> Result<Self, DecodeError> {
+        match vec.len() > N as usize {
+            true => Err(DecodeError::ExceedsMaximumLength { requested_length: vec.len(), allowed_length: N }),
+            false => Ok(VarArray(vec)),
+        }
+    }
+
+    pub fn get_vec(&self) -> &Vec<T> {
+        &self.0
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl <T, const N: i32> Default for VarArray<T, N> {
+    fn default() -> Self {
+        VarArray(Vec::new())
+    }
+}
+
+impl <T, const N: i32> Deref for VarArray<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl <T, const N: i32> TryFrom<Vec<T>> for VarArray<T, N> {
+    type Error = DecodeError;
+    fn try_from(vec: Vec<T>) -> Result<Self, DecodeError> {
+        VarArray::new(vec)
+    }
+}
+
+impl <T: XdrCodec, const N: i32> XdrCodec for VarArray<T, N> {
+    fn encode(&self, write_stream: &mut WriteStream) {
+        self.0.encode(write_stream);
+    }
+
+    fn from_xdr_buffered<R: AsRef<[u8]>>(read_stream: &mut ReadStream<R>) -> Result<Self, DecodeError> {
+        Ok(VarArray(read_var_array(read_stream, N)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `record` split into fragments of the given lengths.
+    fn fragments(record: &[u8], lengths: &[usize]) -> Vec<u8> {
+        let mut write_stream = WriteStream::new();
+        let mut rest = record;
+        for (index, length) in lengths.iter().enumerate() {
+            let last = if index == lengths.len() - 1 { LAST_FRAGMENT } else { 0 };
+            write_stream.write_u32(*length as u32 | last);
+            write_stream.write_raw_bytes(&rest[..*length]);
+            rest = &rest[*length..];
+        }
+        write_stream.result()
+    }
+
+    #[test]
+    fn reassembles_fragments() {
+        let record: Vec<u8> = (0..10).collect();
+        let buf = [fragments(&record, &[3, 2, 5]), vec![0xff]].concat();
+        assert_eq!(record_length(&buf, 10).unwrap(), Some(22));
+        let mut read_stream = ReadStream::new(&buf);
+        assert_eq!(read_record(&mut read_stream, 10).unwrap(), record);
+        assert_eq!(read_stream.get_position(), 22);
+    }
+
+    #[test]
+    fn incomplete_record() {
+        let buf = fragments(&[1, 2, 3, 4, 5], &[2, 3]);
+        for end in [0, 3, 5, 7, 11, 12] {
+            assert_eq!(record_length(&buf[..end], 5).unwrap(), None, "{} bytes", end);
+        }
+        assert_eq!(record_length(&buf, 5).unwrap(), Some(buf.len()));
+    }
+
+    #[test]
+    fn record_over_the_limit() {
+        let buf = fragments(&[0; 6], &[4, 2]);
+        // the second header already tells, before its fragment arrived
+        assert!(matches!(
+            record_length(&buf[..12], 5),
+            Err(DecodeError::RecordTooLarge { requested_length: 6, allowed_length: 5 })
+        ));
+        assert!(matches!(
+            read_record(&mut ReadStream::new(&buf), 5),
+            Err(DecodeError::RecordTooLarge { requested_length: 6, allowed_length: 5 })
+        ));
+    }
+
+    #[test]
+    fn empty_fragment_before_the_last() {
+        let buf = fragments(&[1, 2, 3], &[3, 0, 0]);
+        assert!(matches!(record_length(&buf, 10), Err(DecodeError::EmptyFragment { at_position: 7 })));
+        assert!(matches!(read_record(&mut ReadStream::new(&buf), 10), Err(DecodeError::EmptyFragment { at_position: 7 })));
+        // a peer streaming empty headers is turned away by the first one, not buffered forever
+        assert!(matches!(record_length(&[0; 4], 10), Err(DecodeError::EmptyFragment { at_position: 0 })));
+    }
+
+    #[test]
+    fn too_many_fragments() {
+        let record = vec![1; MAX_FRAGMENTS + 1];
+        let buf = fragments(&record[..MAX_FRAGMENTS], &[1; MAX_FRAGMENTS]);
+        assert_eq!(record_length(&buf, record.len()).unwrap(), Some(5 * MAX_FRAGMENTS));
+        assert_eq!(read_record(&mut ReadStream::new(&buf), record.len()).unwrap(), record[..MAX_FRAGMENTS]);
+
+        let buf = fragments(&record, &[1; MAX_FRAGMENTS + 1]);
+        let at_position = 5 * MAX_FRAGMENTS;
+        assert!(matches!(
+            record_length(&buf, record.len()),
+            Err(DecodeError::TooManyFragments { at_position: position, allowed_fragments: MAX_FRAGMENTS }) if position == at_position
+        ));
+        assert!(matches!(
+            read_record(&mut ReadStream::new(&buf), record.len()),
+            Err(DecodeError::TooManyFragments { at_position: position, .. }) if position == at_position
+        ));
+    }
+
+    #[test]
+    fn empty_record() {
+        let mut write_stream = WriteStream::new();
+        write_record(&mut write_stream, &[]);
+        let buf = write_stream.result();
+        assert_eq!(buf, LAST_FRAGMENT.to_be_bytes());
+        assert_eq!(record_length(&buf, 0).unwrap(), Some(4));
+        assert_eq!(read_record(&mut ReadStream::new(&buf), 0).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn var_array_over_its_maximum() {
+        let array = VarArray::<u32, 2>::new(vec![5, 6]).unwrap();
+        let (decoded, _) = VarArray::<u32, 2>::decoded(array.to_xdr()).unwrap();
+        assert_eq!(decoded, array);
+        assert!(matches!(
+            VarArray::<u32, 2>::new(vec![5, 6, 7]),
+            Err(DecodeError::ExceedsMaximumLength { requested_length: 3, allowed_length: 2 })
+        ));
+        // rejected on the length alone, before any item is read
+        assert!(matches!(
+            VarArray::<u32, 2>::decoded([0, 0, 0, 3]),
+            Err(DecodeError::ExceedsMaximumLength { requested_length: 3, allowed_length: 2 })
+        ));
+    }
+
+    #[test]
+    fn write_record_round_trips() {
+        let record = vec![7; 100];
+        let mut write_stream = WriteStream::new();
+        write_record(&mut write_stream, &record);
+        let buf = write_stream.result();
+        assert_eq!(buf.len(), 104);
+        assert_eq!(read_record(&mut ReadStream::new(&buf), MAX_RECORD_LENGTH).unwrap(), record);
+    }
+}
@@ -1,2 +1 @@
-
-
+include!(concat!(env!("OUT_DIR"), "/constants.rs"));
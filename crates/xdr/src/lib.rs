@@ -1,6 +1,9 @@
+extern crate self as xdr;
+
 pub mod auth_cert;
 pub mod xdr_codec;
 pub use xdr_codec::XdrCodec;
+pub use xdr_derive::XdrCodec;
 pub mod streams;
 pub use streams::{DecodeError, ReadStream};
 
@@ -14,7 +14,11 @@ impl WriteStream {
         self.result.extend_from_slice(value);
         let length = value.len();
         let no_of_padding_bytes = extend_to_multiple_of_4(length) - length;
-        self.result.extend(iter::repeat(0).take(no_of_padding_bytes));
+        self.result.extend(iter::repeat_n(0, no_of_padding_bytes));
+    }
+    /// Writes `value` without XDR padding, e.g. a record marking fragment.
+    pub fn write_raw_bytes(&mut self, value: &[u8]) {
+        self.result.extend_from_slice(value);
     }
     pub fn write_i32(&mut self, value: i32) {
         self.result.extend(value.to_be_bytes().iter());
@@ -25,18 +29,37 @@ impl WriteStream {
     pub fn write_u64(&mut self, value: u64) {
         self.result.extend(value.to_be_bytes().iter());
     }
+    pub fn write_i64(&mut self, value: i64) {
+        self.result.extend(value.to_be_bytes().iter());
+    }
     pub fn result(self) -> Vec<u8> {
         self.result
     }
 }
+/// How deeply structs and unions may nest while decoding. Types like `SCPQuorumSet` contain
+/// themselves, so without a limit a few bytes per level would overflow the stack.
+pub const MAX_DECODE_DEPTH: usize = 200;
+
 pub struct ReadStream<T: AsRef<[u8]>> {
     read_index: usize,
+    depth: usize,
     source: T,
 }
 
 impl<T: AsRef<[u8]>> ReadStream<T> {
     pub fn new(source: T) -> ReadStream<T> {
-        ReadStream { read_index: 0, source }
+        ReadStream { read_index: 0, depth: 0, source }
+    }
+
+    /// Decodes a struct or union one level deeper, failing beyond [`MAX_DECODE_DEPTH`].
+    pub fn nested<V>(&mut self, decode: impl FnOnce(&mut Self) -> Result<V, DecodeError>) -> Result<V, DecodeError> {
+        if self.depth == MAX_DECODE_DEPTH {
+            return Err(DecodeError::NestingTooDeep { at_position: self.read_index, allowed_depth: MAX_DECODE_DEPTH });
+        }
+        self.depth += 1;
+        let result = decode(self);
+        self.depth -= 1;
+        result
     }
 
     fn sudden_end_error(&self, no_of_bytes_to_read: usize) -> DecodeError {
@@ -58,6 +81,13 @@ impl<T: AsRef<[u8]>> ReadStream<T> {
         self.read_index += extend_to_multiple_of_4(no_of_bytes);
         Ok(result)
     }
+    /// Reads `no_of_bytes` without XDR padding, e.g. a record marking fragment.
+    pub fn read_raw_bytes(&mut self, no_of_bytes: usize) -> Result<&[u8], DecodeError> {
+        self.ensure_size(no_of_bytes)?;
+        let result = &self.source.as_ref()[self.read_index..self.read_index + no_of_bytes];
+        self.read_index += no_of_bytes;
+        Ok(result)
+    }
     pub fn read_i32(&mut self) -> Result<i32, DecodeError> {
         let array: &[u8; 4] = self.read_limited_bytes_array(false)?;
         Ok(i32::from_be_bytes(*array))
@@ -70,21 +100,25 @@ impl<T: AsRef<[u8]>> ReadStream<T> {
         let array: &[u8; 8] = self.read_limited_bytes_array(false)?;
         Ok(u64::from_be_bytes(*array))
     }
+    pub fn read_i64(&mut self) -> Result<i64, DecodeError> {
+        let array: &[u8; 8] = self.read_limited_bytes_array(false)?;
+        Ok(i64::from_be_bytes(*array))
+    }
     pub fn read_length(&mut self, only_peek: bool) -> Result<usize, DecodeError> {
         let array: &[u8; 4] = self.read_limited_bytes_array(only_peek)?;
         Ok((u32::from_be_bytes(*array) & 0x7f_ff_ff_ff) as usize)
     }
 
     fn read_limited_bytes_array<const N: usize>(&mut self, only_peek: bool) -> Result<&[u8; N], DecodeError> {
-        let array: Result<&[u8; N], _> = (self.source.as_ref()[self.read_index..self.read_index + N]).try_into();
+        let array = self.source.as_ref().get(self.read_index..self.read_index + N).map(<&[u8; N]>::try_from);
         match array {
-            Ok(array) => {
+            Some(Ok(array)) => {
                 if !only_peek {
                     self.read_index += N;
                 }
                 Ok(array)
             },
-            Err(_) => Err(self.sudden_end_error(N)),
+            _ => Err(self.sudden_end_error(N)),
         }
     }
     pub fn no_of_bytes_left_to_read(&self) -> isize {
@@ -117,4 +151,19 @@ pub enum DecodeError {
     InvalidXdrArchiveLength {
         at_position: usize,
     },
+    RecordTooLarge {
+        requested_length: usize,
+        allowed_length: usize,
+    },
+    TooManyFragments {
+        at_position: usize,
+        allowed_fragments: usize,
+    },
+    EmptyFragment {
+        at_position: usize,
+    },
+    NestingTooDeep {
+        at_position: usize,
+        allowed_depth: usize,
+    },
 }
\ No newline at end of file
@@ -45,6 +45,30 @@ impl XdrCodec for u32 {
     }
 }
 
+impl XdrCodec for i64 {
+    fn encode(&self, write_stream: &mut WriteStream) {
+        write_stream.write_i64(*self);
+    }
+
+    fn from_xdr_buffered<T: AsRef<[u8]>>(read_stream: &mut ReadStream<T>) -> Result<Self, DecodeError> {
+        read_stream.read_i64()
+    }
+}
+
+impl XdrCodec for bool {
+    fn encode(&self, write_stream: &mut WriteStream) {
+        write_stream.write_u32(*self as u32);
+    }
+
+    fn from_xdr_buffered<T: AsRef<[u8]>>(read_stream: &mut ReadStream<T>) -> Result<Self, DecodeError> {
+        match read_stream.read_next_u32()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::InvalidEnumDiscriminator { at_position: read_stream.get_position() }),
+        }
+    }
+}
+
 impl XdrCodec for u64 {
     fn encode(&self, write_stream: &mut WriteStream) {
         write_stream.write_u64(*self);
@@ -70,3 +94,95 @@ impl<T: XdrCodec, const N: usize> XdrCodec for [T; N] {
         result.try_into().map_err(|_| unreachable!())
     }
 }
+
+impl<T: XdrCodec> XdrCodec for Box<T> {
+    fn encode(&self, write_stream: &mut WriteStream) {
+        self.as_ref().encode(write_stream);
+    }
+
+    fn from_xdr_buffered<R: AsRef<[u8]>>(read_stream: &mut ReadStream<R>) -> Result<Self, DecodeError> {
+        Ok(Box::new(T::from_xdr_buffered(read_stream)?))
+    }
+}
+
+/// XDR variable-length array without a maximum size, `T<>`.
+impl<T: XdrCodec> XdrCodec for Vec<T> {
+    fn encode(&self, write_stream: &mut WriteStream) {
+        write_stream.write_u32(self.len() as u32);
+        for item in self.iter() {
+            item.encode(write_stream);
+        }
+    }
+
+    fn from_xdr_buffered<R: AsRef<[u8]>>(read_stream: &mut ReadStream<R>) -> Result<Self, DecodeError> {
+        read_var_array(read_stream, i32::MAX)
+    }
+}
+
+pub(crate) fn read_var_array<T: XdrCodec, R: AsRef<[u8]>>(read_stream: &mut ReadStream<R>, max_length: i32) -> Result<Vec<T>, DecodeError> {
+    let length = read_stream.read_next_u32()? as usize;
+    if length > max_length as usize {
+        return Err(DecodeError::ExceedsMaximumLength { requested_length: length, allowed_length: max_length });
+    }
+    // the length comes from the peer, so the vector grows with the data that is actually there
+    let mut result = Vec::<T>::new();
+    for _ in 0..length {
+        result.push(T::from_xdr_buffered(read_stream)?);
+    }
+    Ok(result)
+}
+
+/// XDR optional `T*`: a boolean flag followed by the value if present.
+impl<T: XdrCodec> XdrCodec for Option<T> {
+    fn encode(&self, write_stream: &mut WriteStream) {
+        match self {
+            Some(value) => {
+                true.encode(write_stream);
+                value.encode(write_stream);
+            },
+            None => false.encode(write_stream),
+        }
+    }
+
+    fn from_xdr_buffered<R: AsRef<[u8]>>(read_stream: &mut ReadStream<R>) -> Result<Self, DecodeError> {
+        match bool::from_xdr_buffered(read_stream)? {
+            true => Ok(Some(T::from_xdr_buffered(read_stream)?)),
+            false => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bool_is_zero_or_one() {
+        assert!(matches!(bool::decoded([0, 0, 0, 0]), Ok((false, 4))));
+        assert!(matches!(bool::decoded([0, 0, 0, 1]), Ok((true, 4))));
+        assert!(matches!(bool::decoded([0, 0, 0, 2]), Err(DecodeError::InvalidEnumDiscriminator { at_position: 4 })));
+    }
+
+    #[test]
+    fn i64_round_trips() {
+        let bytes = (-2i64).to_xdr();
+        assert_eq!(bytes, [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe]);
+        assert!(matches!(i64::decoded(&bytes), Ok((-2, 8))));
+        assert!(matches!(i64::decoded(&bytes[..4]), Err(DecodeError::SuddenEnd { actual_length: 4, expected_length: 8 })));
+    }
+
+    #[test]
+    fn option_decoding() {
+        assert!(matches!(Option::<u32>::decoded([0, 0, 0, 0]), Ok((None, 4))));
+        assert!(matches!(Option::<u32>::decoded([0, 0, 0, 1, 0, 0, 0, 7]), Ok((Some(7), 8))));
+        assert!(matches!(Option::<u32>::decoded([0, 0, 0, 2, 0, 0, 0, 7]), Err(DecodeError::InvalidEnumDiscriminator { at_position: 4 })));
+        // the flag says a value follows, but it is cut short
+        assert!(matches!(Option::<u32>::decoded([0, 0, 0, 1, 0, 0]), Err(DecodeError::SuddenEnd { actual_length: 6, expected_length: 8 })));
+    }
+
+    #[test]
+    fn vec_with_missing_items() {
+        assert_eq!(vec![5u32, 6].to_xdr(), [0, 0, 0, 2, 0, 0, 0, 5, 0, 0, 0, 6]);
+        assert!(matches!(Vec::<u32>::decoded([0, 0, 0, 2, 0, 0, 0, 5]), Err(DecodeError::SuddenEnd { actual_length: 8, expected_length: 12 })));
+    }
+}
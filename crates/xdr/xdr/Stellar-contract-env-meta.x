@@ -0,0 +1,20 @@
+// Copyright 2015 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+%#include "xdr/Stellar-types.h"
+
+namespace stellar
+{
+
+enum SCEnvMetaKind
+{
+    SC_ENV_META_KIND_INTERFACE_VERSION = 0
+};
+
+union SCEnvMetaEntry switch (SCEnvMetaKind kind)
+{
+case SC_ENV_META_KIND_INTERFACE_VERSION:
+    uint64 interfaceVersion;
+};
+}
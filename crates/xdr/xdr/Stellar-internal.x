@@ -0,0 +1,46 @@
+// Copyright 2015 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+%#include "xdr/Stellar-ledger.h"
+
+namespace stellar
+{
+
+union StoredTransactionSet switch (int v)
+{
+case 0:
+	TransactionSet txSet;
+case 1:
+	GeneralizedTransactionSet generalizedTxSet;
+};
+
+struct StoredDebugTransactionSet
+{
+	StoredTransactionSet txSet;
+	uint32 ledgerSeq;
+	StellarValue scpValue;
+};
+
+struct PersistedSCPStateV0
+{
+	SCPEnvelope scpEnvelopes<>;
+	SCPQuorumSet quorumSets<>;
+	StoredTransactionSet txSets<>;
+};
+
+struct PersistedSCPStateV1
+{
+	// Tx sets are saved separately
+	SCPEnvelope scpEnvelopes<>;
+	SCPQuorumSet quorumSets<>;
+};
+
+union PersistedSCPState switch (int v)
+{
+case 0:
+	PersistedSCPStateV0 v0;
+case 1:
+	PersistedSCPStateV1 v1;
+};
+}